@@ -0,0 +1,25 @@
+struct vec3 {
+    x : num,
+    y : num,
+    z : num
+}
+
+overload + (a : vec3, b : vec3) -> vec3 {
+    return vec3 { x : a.x + b.x, y : a.y + b.y, z : a.z + b.z };
+}
+
+let v = vec3 { x : 1, y : 2, z : 3 } + vec3 { x : 1, y : 1, z : 1 };
+print(v);
+
+impl vec3 {
+    fn zero() -> vec3 {
+        return vec3 { x : 0, y : 0, z : 0 };
+    }
+
+    fn length(self) -> num {
+        return (self.x * self.x + self.y * self.y + self.z * self.z) ** 0.5;
+    }
+}
+
+print(v.length());
+print(vec3::zero() + v);
@@ -0,0 +1,206 @@
+use crate::{SourceMap, Span};
+
+//a piece of the source that gets underlined, together with what we want to say about it
+#[derive(Clone, Debug)]
+pub struct Label{
+    pub span : Span,
+    pub message : String
+}
+
+//everything we know about an error in a form that can be printed against the source text
+#[derive(Clone, Debug)]
+pub struct Diagnostic{
+    pub message : String,
+    //the place that caused the error, underlined with ^
+    pub primary : Option<Label>,
+    //places that explain the error, underlined with -
+    pub secondary : Vec<Label>,
+    //printed as "= help: ..." below the source
+    pub help : Vec<String>
+}
+
+const RED : &str = "\x1b[1;31m";
+const BLUE : &str = "\x1b[1;34m";
+const BOLD : &str = "\x1b[1m";
+const RESET : &str = "\x1b[0m";
+
+impl Diagnostic{
+    pub fn new(message : impl Into<String>) -> Self{
+        Self{
+            message : message.into(),
+            primary : None,
+            secondary : Vec::new(),
+            help : Vec::new()
+        }
+    }
+
+    pub fn with_label(mut self, span : Span, message : impl Into<String>) -> Self{
+        self.primary = Some(Label{
+            span,
+            message : message.into()
+        });
+        self
+    }
+
+    pub fn with_secondary(mut self, span : Span, message : impl Into<String>) -> Self{
+        self.secondary.push(Label{
+            span,
+            message : message.into()
+        });
+        self
+    }
+
+    pub fn with_help(mut self, help : impl Into<String>) -> Self{
+        self.help.push(help.into());
+        self
+    }
+
+    //renders the diagnostic against the file its primary label points into
+    pub fn render_in(&self, sources : &SourceMap, colored : bool) -> String{
+        let file = self.primary.as_ref().and_then(|primary| sources.get(primary.span.file));
+
+        match file {
+            Some(file) => {
+                //labels are printed against the text of one file, so the ones from other files are left out
+                let mut diagnostic = self.clone();
+                diagnostic.secondary.retain(|label| label.span.file == file.id);
+
+                diagnostic.render(&file.name(), &file.text, colored)
+            },
+            None => self.render("<unknown>", "", colored)
+        }
+    }
+
+    //renders the diagnostic the way compilers usually do:
+    //
+    //error: mismatched types
+    // --> main.bf:2:18
+    //  |
+    //2 | let y : string = 5;
+    //  |                  ^ expected `string`, found `num`
+    //
+    //colored adds ansi escape codes, which should only be used when printing to a terminal
+    pub fn render(&self, file_name : &str, source : &str, colored : bool) -> String{
+        let paint = |color : &str, text : &str| {
+            if colored {
+                format!("{}{}{}", color, text, RESET)
+            } else {
+                text.to_string()
+            }
+        };
+
+        let mut output = format!("{}{}\n", paint(RED, "error"), paint(BOLD, &format!(": {}", self.message)));
+
+        //every label together with the character used to underline it
+        let mut labels : Vec<(&Label, &str, &str)> = Vec::new();
+        if let Some(primary) = &self.primary{
+            labels.push((primary, "^", RED));
+        }
+        for secondary in self.secondary.iter(){
+            labels.push((secondary, "-", BLUE));
+        }
+
+        let location = match &self.primary {
+            Some(primary) => format!("{}:{}:{}", file_name, primary.span.start.line + 1, primary.span.start.column + 1),
+            None => file_name.to_string()
+        };
+
+        let lines : Vec<&str> = source.split('\n').collect();
+        let gutter_width = labels.iter()
+            .map(|(label, _, _)| (label.span.start.line + 1).to_string().len())
+            .max()
+            .unwrap_or(1);
+        let gutter = " ".repeat(gutter_width);
+
+        output += &format!("{}{} {}\n", gutter, paint(BLUE, "-->"), location);
+
+        if !labels.is_empty(){
+            output += &format!("{} {}\n", gutter, paint(BLUE, "|"));
+        }
+
+        labels.sort_by_key(|(label, _, _)| label.span.start.offset);
+
+        let mut printed_line = None;
+        for (label, underline, color) in labels.iter(){
+            let line_number = label.span.start.line as usize;
+            let line = lines.get(line_number).unwrap_or(&"").replace('\t', " ");
+            let line_length = line.chars().count();
+
+            //labels on the same line share the printed source line
+            if printed_line != Some(line_number){
+                output += &format!("{} {} {}\n", paint(BLUE, &format!("{:>width$}", line_number + 1, width = gutter_width)), paint(BLUE, "|"), line);
+                printed_line = Some(line_number);
+            }
+
+            //spans over several lines are only underlined until the end of their first line
+            let start = (label.span.start.column as usize).min(line_length);
+            let end = if label.span.end.line as usize == line_number {
+                (label.span.end.column as usize).min(line_length)
+            } else {
+                line_length
+            };
+            let width = end.saturating_sub(start).max(1);
+
+            let marker = underline.repeat(width);
+            let message = if label.message.is_empty() { String::new() } else { format!(" {}", label.message) };
+            output += &format!("{} {} {}{}\n", gutter, paint(BLUE, "|"), " ".repeat(start), paint(color, &format!("{}{}", marker, message)));
+        }
+
+        if !labels.is_empty() && !self.help.is_empty(){
+            output += &format!("{} {}\n", gutter, paint(BLUE, "|"));
+        }
+
+        for help in self.help.iter(){
+            output += &format!("{} {} {}\n", gutter, paint(BLUE, "="), paint(BOLD, &format!("help: {}", help)));
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::*;
+
+    fn first_error(source : &'static str) -> Diagnostic{
+        type_check(parse(lex(source).unwrap()).unwrap()).unwrap_err().diagnostic()
+    }
+
+    #[test]
+    fn labels_underline_their_spans(){
+        let source = "let a : num = 1;\nlet y : string = a;";
+
+        assert_eq!(first_error(source).render("main.bf", source, false), "\
+error: mismatched types
+ --> main.bf:2:18
+  |
+2 | let y : string = a;
+  |     - expected because of this
+  |                  ^ expected `string`, found `num`
+");
+    }
+
+    #[test]
+    fn help_is_printed_below_the_source(){
+        let source = "let a : num = 1;\nprint(a + true);";
+
+        assert_eq!(first_error(source).render("main.bf", source, false), "\
+error: `+` cannot be applied to `num` and `bool`
+ --> main.bf:2:9
+  |
+2 | print(a + true);
+  |         ^ no implementation for these types
+  |
+  = help: you can define one with `overload + (a : num, b : bool) -> ... { ... }`
+");
+    }
+
+    #[test]
+    fn colors_are_optional(){
+        let source = "let y : string = 1;";
+        let diagnostic = first_error(source);
+
+        assert!(diagnostic.render("main.bf", source, true).contains("\x1b["));
+        assert!(!diagnostic.render("main.bf", source, false).contains("\x1b["));
+    }
+}
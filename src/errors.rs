@@ -1,17 +1,570 @@
 use crate::*;
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LexErrorKind{
+    //the end of the file came before the closing "
+    UnterminatedString,
+    //the end of the file came before the closing */
+    UnterminatedComment,
+    //the reason says what is wrong with it
+    MalformedNumber(String),
+    InvalidEscape(String),
+    UnexpectedCharacter(char)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LexError{
+    pub kind : LexErrorKind,
+    pub span : Span
+}
+
+//the details of Error::TypeMismatch, kept behind a box since the types make it much larger than the other errors
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeMismatch{
+    pub expected : Type,
+    pub found : Type,
+    pub span : Span,
+    //where the expected type comes from, for example the annotation of a let
+    pub expected_from : Option<Span>,
+    //the variable without an annotation whose type the checker inferred, and what it inferred
+    pub inferred_from : Option<(Span, Type)>
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Error{
     Nil,
+    //the tokens ran out in the middle of something, the span is the end of the file
+    UnexpectedEof(Span),
+    Lex(LexError),
     UnexpectedToken{
         expected : TokenType,
         unexpected : Token
     },
+    //an empty expected list means the token should not be there at all (like a trailing comma)
     UnexpectedTokenOfMany{
         expected : Vec<TokenType>,
         unexpected : Token
     },
-    TypeNotFound
+    ExpectedExpression(Token),
+    //a number literal too large for a num
+    NumberOutOfRange{
+        number : String,
+        span : Span
+    },
+    TypeNotFound{
+        name : String,
+        span : Span
+    },
+    //type L = L | nil, an alias that only ever resolves to itself
+    RecursiveAlias{
+        name : String,
+        span : Span
+    },
+    TypeMismatch(Box<TypeMismatch>),
+
+    //these are found by the type checker or, for untyped code, while the program is running
+    UndefinedVariable{
+        name : String,
+        span : Span
+    },
+    ConstantReassignment{
+        name : String,
+        span : Span
+    },
+    InvalidAssignmentTarget(Span),
+    InvalidOperands{
+        operator : Token,
+        operands : Vec<String>
+    },
+    InvalidCondition{
+        found : String,
+        span : Span
+    },
+    NotCallable{
+        found : String,
+        span : Span
+    },
+    NotIterable{
+        found : String,
+        span : Span
+    },
+    //the start, end or step of for i = a to b step c is not a number
+    InvalidBound{
+        found : String,
+        span : Span
+    },
+    //for i = a to b step 0 would never reach the end
+    ZeroStep(Span),
+    NotStringifiable{
+        found : String,
+        span : Span
+    },
+    ArityMismatch{
+        expected : usize,
+        found : usize,
+        span : Span
+    },
+    //Pair<num> for a struct Pair<A, B>, or arguments for something that is not generic at all
+    TypeArgumentMismatch{
+        name : String,
+        expected : usize,
+        found : usize,
+        span : Span
+    },
+    FieldNotFound{
+        field : String,
+        span : Span
+    },
+    //a struct literal that sets the same field twice, previous is where it was set first
+    DuplicateField{
+        field : String,
+        span : Span,
+        previous : Span
+    },
+    //a struct literal that leaves out some of the declared fields
+    MissingFields{
+        name : String,
+        fields : Vec<String>,
+        span : Span
+    },
+    //Name { ... } or impl Name where Name was declared with type instead of struct
+    NotAStruct{
+        name : String,
+        span : Span
+    },
+    //Name::Variant where Name has no such variant, variants are the ones it does have
+    VariantNotFound{
+        enum_name : String,
+        variant : String,
+        variants : Vec<String>,
+        span : Span
+    },
+    //a variant built with the wrong kind of payload, usage says how it is built instead
+    PayloadMismatch{
+        variant : String,
+        usage : String,
+        span : Span
+    },
+    MethodNotFound{
+        struct_name : String,
+        method : String,
+        span : Span
+    },
+    //x.function() where function is in an impl block but does not take self
+    NotAMethod{
+        struct_name : String,
+        function : String,
+        span : Span
+    },
+    IndexOutOfBounds{
+        index : i64,
+        length : usize,
+        span : Span
+    },
+    DivisionByZero(Token),
+    //calls nested deeper than the interpreter allows, usually a recursion that never stops
+    CallDepthExceeded{
+        limit : usize,
+        span : Span
+    },
+    //a match arm whose pattern can never fit the type of the value
+    PatternMismatch{
+        found : Type,
+        span : Span
+    },
+    //a match arm that only matches what the arms before it already did
+    UnreachablePattern(Span),
+    //the missing cases are shown as patterns
+    NonExhaustiveMatch{
+        missing : Vec<String>,
+        span : Span
+    },
+    //no arm of a match fit the value, the type checker prevents this for typed values
+    NoMatchingArm{
+        found : String,
+        span : Span
+    },
+    //break and continue outside of loops, return outside of functions
+    InvalidControlFlow{
+        keyword : String,
+        span : Span
+    }
+}
+
+//lists token types the way a sentence would: `a`, `b` or `c`
+fn one_of(expected : &[TokenType]) -> String{
+    let names = expected.iter().map(|x| x.to_string()).collect::<Vec<String>>();
+
+    match names.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+        None => String::new()
+    }
+}
+
+fn plural(count : usize, word : &str) -> String{
+    if count == 1 {
+        format!("{} {}", count, word)
+    } else {
+        format!("{} {}s", count, word)
+    }
 }
 
+impl Error{
+    //the part of the source the error is about, if we know it
+    pub fn span(&self) -> Option<Span>{
+        match self {
+            Error::Nil => None,
+            Error::Lex(error) => Some(error.span),
+            Error::UnexpectedToken { unexpected, .. }
+                | Error::UnexpectedTokenOfMany { unexpected, .. }
+                | Error::ExpectedExpression(unexpected) => Some(unexpected.span),
+            Error::InvalidOperands { operator, .. } | Error::DivisionByZero(operator) => Some(operator.span),
+            Error::InvalidAssignmentTarget(span) | Error::UnreachablePattern(span) | Error::ZeroStep(span) | Error::UnexpectedEof(span) => Some(*span),
+            Error::TypeMismatch(mismatch) => Some(mismatch.span),
+            Error::NumberOutOfRange { span, .. }
+                | Error::TypeNotFound { span, .. }
+                | Error::RecursiveAlias { span, .. }
+                | Error::UndefinedVariable { span, .. }
+                | Error::ConstantReassignment { span, .. }
+                | Error::InvalidCondition { span, .. }
+                | Error::NotCallable { span, .. }
+                | Error::NotIterable { span, .. }
+                | Error::InvalidBound { span, .. }
+                | Error::NotStringifiable { span, .. }
+                | Error::ArityMismatch { span, .. }
+                | Error::TypeArgumentMismatch { span, .. }
+                | Error::FieldNotFound { span, .. }
+                | Error::DuplicateField { span, .. }
+                | Error::MissingFields { span, .. }
+                | Error::NotAStruct { span, .. }
+                | Error::VariantNotFound { span, .. }
+                | Error::PayloadMismatch { span, .. }
+                | Error::MethodNotFound { span, .. }
+                | Error::NotAMethod { span, .. }
+                | Error::IndexOutOfBounds { span, .. }
+                | Error::CallDepthExceeded { span, .. }
+                | Error::PatternMismatch { span, .. }
+                | Error::NonExhaustiveMatch { span, .. }
+                | Error::NoMatchingArm { span, .. }
+                | Error::InvalidControlFlow { span, .. } => Some(*span),
+        }
+    }
+
+    //the one line summary of what went wrong
+    pub fn message(&self) -> String{
+        match self {
+            Error::Nil | Error::UnexpectedEof(_) => "unexpected end of input".to_string(),
+            Error::Lex(error) => {
+                match &error.kind {
+                    LexErrorKind::UnterminatedString => "unterminated string".to_string(),
+                    LexErrorKind::UnterminatedComment => "unterminated block comment".to_string(),
+                    LexErrorKind::MalformedNumber(_) => "malformed number".to_string(),
+                    LexErrorKind::InvalidEscape(_) => "invalid escape sequence".to_string(),
+                    LexErrorKind::UnexpectedCharacter(char) => format!("unexpected character `{}`", char.escape_default())
+                }
+            },
+            Error::UnexpectedToken { expected, unexpected } => {
+                format!("expected {}, found {}", expected, unexpected.r#type)
+            },
+            Error::UnexpectedTokenOfMany { expected, unexpected } => {
+                if expected.is_empty() {
+                    format!("unexpected {}", unexpected.r#type)
+                } else {
+                    format!("expected {}, found {}", one_of(expected), unexpected.r#type)
+                }
+            },
+            Error::ExpectedExpression(unexpected) => format!("expected an expression, found {}", unexpected.r#type),
+            Error::NumberOutOfRange { .. } => "number literal is out of range".to_string(),
+            Error::TypeNotFound { name, .. } => format!("cannot find type `{}`", name),
+            Error::RecursiveAlias { name, .. } => format!("type `{}` is defined as itself", name),
+            Error::TypeMismatch(_) => "mismatched types".to_string(),
+            Error::UndefinedVariable { name, .. } => format!("cannot find `{}` in this scope", name),
+            Error::ConstantReassignment { name, .. } => format!("cannot assign twice to constant `{}`", name),
+            Error::InvalidAssignmentTarget(_) => "invalid left-hand side of assignment".to_string(),
+            Error::InvalidOperands { operator, operands } => {
+                let operands = operands.iter().map(|x| format!("`{}`", x)).collect::<Vec<String>>();
+                format!("{} cannot be applied to {}", operator.r#type, operands.join(" and "))
+            },
+            Error::InvalidCondition { found, .. } => format!("conditions have to be `bool`, found `{}`", found),
+            Error::NotCallable { found, .. } => format!("`{}` is not a function", found),
+            Error::NotIterable { found, .. } => format!("`{}` cannot be iterated over", found),
+            Error::InvalidBound { found, .. } => format!("the bounds of a for loop have to be `num`, found `{}`", found),
+            Error::ZeroStep(_) => "a `step` of 0 would never finish".to_string(),
+            Error::NotStringifiable { found, .. } => format!("`{}` cannot be put into a string", found),
+            Error::ArityMismatch { expected, found, .. } => {
+                let supplied = if *found == 1 { "was" } else { "were" };
+                format!("this function takes {} but {} {} supplied", plural(*expected, "argument"), plural(*found, "argument"), supplied)
+            },
+            Error::TypeArgumentMismatch { name, expected, found, .. } => {
+                let supplied = if *found == 1 { "was" } else { "were" };
+                format!("`{}` takes {} but {} {} supplied", name, plural(*expected, "type argument"), plural(*found, "type argument"), supplied)
+            },
+            Error::FieldNotFound { field, .. } => format!("no field `{}`", field),
+            Error::DuplicateField { field, .. } => format!("field `{}` is set more than once", field),
+            Error::MissingFields { name, fields, .. } => {
+                let fields = fields.iter().map(|x| format!("`{}`", x)).collect::<Vec<String>>();
+                format!("missing {} {} in `{}`", if fields.len() == 1 { "field" } else { "fields" }, fields.join(", "), name)
+            },
+            Error::NotAStruct { name, .. } => format!("`{}` is not a struct", name),
+            Error::VariantNotFound { enum_name, variant, .. } => format!("no variant `{}` in `{}`", variant, enum_name),
+            Error::PayloadMismatch { variant, .. } => format!("`{}` is not built like this", variant),
+            Error::MethodNotFound { struct_name, method, .. } => format!("no function `{}` for `{}`", method, struct_name),
+            Error::NotAMethod { struct_name, function, .. } => format!("`{}::{}` is not a method", struct_name, function),
+            Error::IndexOutOfBounds { index, length, .. } => format!("index {} is out of bounds for a length of {}", index, length),
+            Error::DivisionByZero(_) => "division by zero".to_string(),
+            Error::CallDepthExceeded { limit, .. } => format!("calls are nested more than {} levels deep", limit),
+            Error::PatternMismatch { found, .. } => format!("this pattern can never match a `{}`", found),
+            Error::UnreachablePattern(_) => "unreachable pattern".to_string(),
+            Error::NonExhaustiveMatch { missing, .. } => {
+                let missing = missing.iter().map(|x| format!("`{}`", x)).collect::<Vec<String>>();
+                let (last, rest) = missing.split_last().map(|(last, rest)| (last.to_string(), rest.join(", "))).unwrap_or_default();
+
+                if rest.is_empty() {
+                    format!("pattern {} is not covered", last)
+                } else {
+                    format!("patterns {} and {} are not covered", rest, last)
+                }
+            },
+            Error::NoMatchingArm { found, .. } => format!("no arm of the match fits a `{}`", found),
+            Error::InvalidControlFlow { keyword, .. } => {
+                match keyword.as_str() {
+                    "return" => "`return` outside of a function".to_string(),
+                    _ => format!("`{}` outside of a loop", keyword)
+                }
+            }
+        }
+    }
+
+    //turns the error into something that can be shown against the source text
+    pub fn diagnostic(&self) -> Diagnostic{
+        let mut diagnostic = Diagnostic::new(self.message());
 
+        let span = match self.span() {
+            Some(span) => span,
+            None => return diagnostic
+        };
+
+        match self {
+            Error::Lex(error) => {
+                match &error.kind {
+                    LexErrorKind::UnterminatedString => {
+                        diagnostic = diagnostic
+                            .with_label(span, "this string is never closed")
+                            .with_help("add a `\"` where the string should end");
+                    },
+                    LexErrorKind::UnterminatedComment => {
+                        diagnostic = diagnostic
+                            .with_label(span, "this comment is never closed")
+                            .with_help("every `/*` needs its own `*/`, also when they are nested");
+                    },
+                    LexErrorKind::MalformedNumber(reason) => {
+                        diagnostic = diagnostic.with_label(span, reason.to_string());
+                    },
+                    LexErrorKind::InvalidEscape(reason) => {
+                        diagnostic = diagnostic
+                            .with_label(span, reason.to_string())
+                            .with_help("the escape sequences are \\n, \\t, \\r, \\0, \\\\, \\\", \\$ and \\u{...}");
+                    },
+                    LexErrorKind::UnexpectedCharacter(_) => {
+                        diagnostic = diagnostic.with_label(span, "not part of the language");
+                    }
+                }
+            },
+            Error::UnexpectedToken { expected, .. } => {
+                diagnostic = diagnostic.with_label(span, format!("expected {}", expected));
+
+                if *expected == TokenType::SEMICOLON {
+                    diagnostic = diagnostic.with_help("statements have to end with `;`");
+                }
+            },
+            Error::UnexpectedTokenOfMany { expected, unexpected } => {
+                if expected.is_empty() {
+                    diagnostic = diagnostic
+                        .with_label(span, "trailing commas are not allowed here")
+                        .with_help(format!("remove the `,` before {} or add another item after it", unexpected.r#type));
+                } else {
+                    diagnostic = diagnostic.with_label(span, format!("expected {}", one_of(expected)));
+                }
+            },
+            Error::ExpectedExpression(_) => {
+                diagnostic = diagnostic.with_label(span, "expected a value here");
+            },
+            Error::UnexpectedEof(_) => {
+                diagnostic = diagnostic.with_label(span, "the file ends here");
+            },
+            Error::NumberOutOfRange { .. } => {
+                diagnostic = diagnostic
+                    .with_label(span, "too large for a `num`")
+                    .with_help(format!("the largest `num` is about {:e}", f64::MAX));
+            },
+            Error::TypeNotFound { name, .. } => {
+                diagnostic = diagnostic
+                    .with_label(span, "not declared")
+                    .with_help(format!("declare it with `type {} = ...;`, `struct {} {{ ... }}` or `enum {} {{ ... }}`", name, name, name));
+            },
+            Error::RecursiveAlias { .. } => {
+                diagnostic = diagnostic
+                    .with_label(span, "never resolves to a type")
+                    .with_help("put the recursive part inside of a struct, an enum or an array, like `type L = L[] | nil;`");
+            },
+            Error::TypeMismatch(mismatch) => {
+                let TypeMismatch { expected, found, expected_from, inferred_from, .. } = mismatch.as_ref();
+                diagnostic = diagnostic.with_label(span, format!("expected `{}`, found `{}`", expected, found));
+
+                if let Some(expected_from) = expected_from{
+                    diagnostic = diagnostic.with_secondary(*expected_from, "expected because of this");
+                }
+
+                if let Some((inferred_from, inferred)) = inferred_from{
+                    diagnostic = diagnostic
+                        .with_secondary(*inferred_from, format!(":inferred `{}`", inferred))
+                        .with_help("this name has no annotation, so its type was inferred from its value or uses, annotate it to choose another one");
+                }
+            },
+            Error::UndefinedVariable { name, .. } => {
+                diagnostic = diagnostic
+                    .with_label(span, "not found in this scope")
+                    .with_help(format!("declare it first, for example with `let {} = ...;`", name));
+            },
+            Error::ConstantReassignment { .. } => {
+                diagnostic = diagnostic
+                    .with_label(span, "cannot be reassigned")
+                    .with_help("declare it with `let` instead of `const` to make it reassignable");
+            },
+            Error::InvalidAssignmentTarget(_) => {
+                diagnostic = diagnostic
+                    .with_label(span, "cannot be assigned to")
+                    .with_help("only names, fields and indices can be assigned to");
+            },
+            Error::InvalidOperands { operator, .. } if operator.r#type == TokenType::DOTDOTDOT => {
+                diagnostic = diagnostic
+                    .with_label(span, "cannot be spread")
+                    .with_help("only objects and structs can be spread into an object");
+            },
+            Error::InvalidOperands { operator, operands } => {
+                let arguments = operands.iter().enumerate()
+                    .map(|(index, operand)| format!("{} : {}", ["a", "b"].get(index).unwrap_or(&"x"), operand))
+                    .collect::<Vec<String>>();
+
+                diagnostic = diagnostic
+                    .with_label(span, "no implementation for these types")
+                    .with_help(format!("you can define one with `overload {} ({}) -> ... {{ ... }}`", operator.r#type.to_string().trim_matches('`'), arguments.join(", ")));
+            },
+            Error::InvalidCondition { .. } => {
+                diagnostic = diagnostic.with_label(span, "expected `bool`");
+            },
+            Error::NotCallable { .. } => {
+                diagnostic = diagnostic.with_label(span, "called here");
+            },
+            Error::NotIterable { .. } => {
+                diagnostic = diagnostic.with_label(span, "not iterable");
+            },
+            Error::InvalidBound { .. } => {
+                diagnostic = diagnostic.with_label(span, "expected `num`");
+            },
+            Error::ZeroStep(_) => {
+                diagnostic = diagnostic.with_label(span, "this is 0");
+            },
+            Error::NotStringifiable { .. } => {
+                diagnostic = diagnostic.with_label(span, "interpolated here");
+            },
+            Error::ArityMismatch { expected, .. } => {
+                diagnostic = diagnostic.with_label(span, format!("expected {}", plural(*expected, "argument")));
+            },
+            Error::TypeArgumentMismatch { name, expected, .. } => {
+                diagnostic = diagnostic.with_label(span, format!("expected {}", plural(*expected, "type argument")));
+
+                if *expected == 0 {
+                    diagnostic = diagnostic.with_help(format!("`{}` is not generic, use it without `<...>`", name));
+                }
+            },
+            Error::FieldNotFound { .. } => {
+                diagnostic = diagnostic.with_label(span, "unknown field");
+            },
+            Error::DuplicateField { previous, .. } => {
+                diagnostic = diagnostic
+                    .with_label(span, "set again here")
+                    .with_secondary(*previous, "first set here");
+            },
+            Error::MissingFields { fields, .. } => {
+                diagnostic = diagnostic.with_label(span, format!("missing {}", plural(fields.len(), "field")));
+            },
+            Error::NotAStruct { .. } => {
+                diagnostic = diagnostic
+                    .with_label(span, "not a struct")
+                    .with_help("only types declared with `struct` can be built with `{ ... }` or have an `impl`");
+            },
+            Error::VariantNotFound { enum_name, variants, .. } => {
+                diagnostic = diagnostic.with_label(span, "not a variant");
+
+                if !variants.is_empty() {
+                    let variants = variants.iter().map(|x| format!("`{}::{}`", enum_name, x)).collect::<Vec<String>>();
+                    diagnostic = diagnostic.with_help(format!("the variants are {}", variants.join(", ")));
+                }
+            },
+            Error::PayloadMismatch { usage, .. } => {
+                diagnostic = diagnostic
+                    .with_label(span, "wrong kind of payload")
+                    .with_help(format!("build it as `{}`", usage));
+            },
+            Error::MethodNotFound { struct_name, .. } => {
+                diagnostic = diagnostic
+                    .with_label(span, "not found")
+                    .with_help(format!("functions are added to a struct with `impl {} {{ fn ... }}`", struct_name));
+            },
+            Error::NotAMethod { struct_name, function, .. } => {
+                diagnostic = diagnostic
+                    .with_label(span, "does not take `self`")
+                    .with_help(format!("call it as `{}::{}(...)`", struct_name, function));
+            },
+            Error::IndexOutOfBounds { .. } => {
+                diagnostic = diagnostic.with_label(span, "out of bounds");
+            },
+            Error::DivisionByZero(_) => {
+                diagnostic = diagnostic.with_label(span, "the right side is 0");
+            },
+            Error::CallDepthExceeded { .. } => {
+                diagnostic = diagnostic
+                    .with_label(span, "one call too many")
+                    .with_help("a function that calls itself needs a case in which it stops doing so");
+            },
+            Error::PatternMismatch { found, .. } => {
+                diagnostic = diagnostic.with_label(span, format!("the value is a `{}`", found));
+            },
+            Error::UnreachablePattern(_) => {
+                diagnostic = diagnostic
+                    .with_label(span, "never reached")
+                    .with_help("the arms before this one already match everything it would");
+            },
+            Error::NonExhaustiveMatch { missing, .. } => {
+                diagnostic = diagnostic
+                    .with_label(span, format!("{} not covered", plural(missing.len(), "case")))
+                    .with_help("add an arm for each of them or a `_ =>` arm at the end");
+            },
+            Error::NoMatchingArm { .. } => {
+                diagnostic = diagnostic
+                    .with_label(span, "nothing matched")
+                    .with_help("add a `_ =>` arm at the end");
+            },
+            Error::InvalidControlFlow { .. } => {
+                diagnostic = diagnostic.with_label(span, "not allowed here");
+            },
+            Error::Nil => ()
+        }
+
+        diagnostic
+    }
+}
+
+impl From<LexError> for Error{
+    fn from(error : LexError) -> Self{
+        Error::Lex(error)
+    }
+}
+
+impl std::fmt::Display for Error{
+    fn fmt(&self, f : &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
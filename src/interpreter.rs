@@ -0,0 +1,1501 @@
+use crate::{Expression, ExpressionKind, Error, ForRange, ObjectEntry, Payload, Pattern, PatternKind, PatternPayload, Span, Token, TokenType, Type, TypedName};
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+//objects and struct instances share the same layout, keys and values are kept in declaration order
+//(the same way Type::ObjectType stores them)
+#[derive(Clone, Debug, Default)]
+pub struct Fields{
+    pub keys : Vec<String>,
+    pub values : Vec<Value>
+}
+
+impl Fields{
+    pub fn get(&self, key : &str) -> Option<Value>{
+        let index = self.keys.iter().position(|x| x == key)?;
+        self.values.get(index).cloned()
+    }
+
+    pub fn set(&mut self, key : String, value : Value){
+        if let Some(index) = self.keys.iter().position(|x| *x == key){
+            self.values[index] = value;
+        } else {
+            self.keys.push(key);
+            self.values.push(value);
+        }
+    }
+}
+
+pub struct Closure{
+    pub name : Option<String>,
+    pub arguments : Vec<TypedName>,
+    pub body : Expression,
+    //the environment the function was created in, this is what makes closures work. a function that is
+    //stored in the environment it holds (named functions, methods, overloads) keeps that environment and
+    //with it itself alive, so these scopes are never freed. we accept the leak, a Weak would free scopes
+    //that returned closures still need. it only grows with calls that declare functions inside of them,
+    //the repl breaks the cycles of its global scope when it is reset with Environment::clear
+    pub environment : Rc<RefCell<Environment>>
+}
+
+//closures hold their environment which can hold the closure itself again, so we cannot derive debug
+impl fmt::Debug for Closure{
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Closure({})", self.name.clone().unwrap_or("anonymous".to_string()))
+    }
+}
+
+#[derive(Clone)]
+pub struct NativeFunction{
+    pub name : &'static str,
+    //none means the function takes any number of arguments
+    pub arity : Option<usize>,
+    //the span is the call site, natives have no source of their own to point errors at
+    pub function : fn(Vec<Value>, Span) -> Result<Value, Error>
+}
+
+impl fmt::Debug for NativeFunction{
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NativeFunction({})", self.name)
+    }
+}
+
+//the data inside of an enum value, laid out like the payload of its variant
+#[derive(Clone, Debug)]
+pub enum PayloadValue{
+    Unit,
+    Tuple(Vec<Value>),
+    Fields(Rc<RefCell<Fields>>)
+}
+
+#[derive(Clone, Debug)]
+pub enum Value{
+    Num(f64),
+    Str(String),
+    Bool(bool),
+    Nil,
+
+    Array(Rc<RefCell<Vec<Value>>>),
+    Object(Rc<RefCell<Fields>>),
+    Struct{
+        name : String,
+        fields : Rc<RefCell<Fields>>
+    },
+    //name is the enum, variant the one of its variants this value is
+    Variant{
+        name : String,
+        variant : String,
+        payload : PayloadValue
+    },
+
+    Closure(Rc<Closure>),
+    NativeFunction(NativeFunction),
+    //Name::Variant of a tuple variant, calling it builds the value
+    Constructor{
+        name : String,
+        variant : String,
+        arity : usize
+    }
+}
+
+impl Value{
+    //the name of the type of a value at runtime, structs are named after their declaration
+    pub fn type_name(&self) -> String{
+        match self {
+            Value::Num(_) => "num".to_string(),
+            Value::Str(_) => "string".to_string(),
+            Value::Bool(_) => "bool".to_string(),
+            Value::Nil => "nil".to_string(),
+            Value::Array(_) => "array".to_string(),
+            Value::Object(_) => "object".to_string(),
+            Value::Struct { name, .. } | Value::Variant { name, .. } => name.to_string(),
+            Value::Closure(_) | Value::NativeFunction(_) | Value::Constructor { .. } => "fn".to_string()
+        }
+    }
+
+    //checks (shallowly) if a value can be used where the type is expected, used to pick overloads
+    //aliases are looked up in the environment, so they match what they stand for
+    pub fn matches_type(&self, check_type : &Type, environment : &Environment) -> bool{
+        if let Type::CustomType(name) | Type::Applied { name, .. } = check_type {
+            if let Some((generics, alias)) = environment.get_alias(name){
+                //maybe<num> is what maybe stands for with its parameter replaced by num
+                let arguments = match check_type {
+                    Type::Applied { arguments, .. } => arguments.clone(),
+                    _ => vec![]
+                };
+                let alias = alias.replace(&|x| match x {
+                    Type::Param(param) => generics.iter().position(|x| x == param).and_then(|index| arguments.get(index).cloned()),
+                    _ => None
+                });
+
+                return self.matches_type(&alias, environment)
+            }
+        }
+
+        match (self, check_type) {
+            //a parameter could have been anything
+            (_, Type::AnyType | Type::Param(_)) => true,
+            (Value::Num(_), Type::NumType) => true,
+            (Value::Str(_), Type::StrType) => true,
+            (Value::Bool(_), Type::BoolType) => true,
+            (Value::Nil, Type::NullType) => true,
+            (Value::Array(_), Type::ArrayType(_)) => true,
+            (Value::Object(_), Type::ObjectType { .. }) => true,
+            (Value::Struct { name, .. } | Value::Variant { name, .. }, Type::CustomType(type_name) | Type::Applied { name : type_name, .. }) => name == type_name,
+            (Value::Closure(_) | Value::Constructor { .. }, Type::FunctionType { .. }) => true,
+            (Value::NativeFunction(_), Type::FunctionType { .. }) => true,
+            (_, Type::UnionType(options)) => options.iter().any(|option| self.matches_type(option, environment)),
+            _ => false
+        }
+    }
+
+    //structural equality for data, functions are only equal to themselves
+    pub fn equals(&self, other : &Value) -> bool{
+        match (self, other) {
+            (Value::Num(a), Value::Num(b)) => a == b,
+            (Value::Str(a), Value::Str(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Nil, Value::Nil) => true,
+            (Value::Array(a), Value::Array(b)) => {
+                let (a, b) = (a.borrow(), b.borrow());
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| x.equals(y))
+            },
+            (Value::Object(a), Value::Object(b)) => fields_equal(&a.borrow(), &b.borrow()),
+            (Value::Struct { name : a_name, fields : a }, Value::Struct { name : b_name, fields : b }) => {
+                a_name == b_name && fields_equal(&a.borrow(), &b.borrow())
+            },
+            (Value::Variant { name : a_name, variant : a_variant, payload : a }, Value::Variant { name : b_name, variant : b_variant, payload : b }) => {
+                a_name == b_name && a_variant == b_variant && match (a, b) {
+                    (PayloadValue::Unit, PayloadValue::Unit) => true,
+                    (PayloadValue::Tuple(a), PayloadValue::Tuple(b)) => a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| x.equals(y)),
+                    (PayloadValue::Fields(a), PayloadValue::Fields(b)) => fields_equal(&a.borrow(), &b.borrow()),
+                    _ => false
+                }
+            },
+            (Value::Closure(a), Value::Closure(b)) => Rc::ptr_eq(a, b),
+            (Value::Constructor { name : a_name, variant : a, .. }, Value::Constructor { name : b_name, variant : b, .. }) => a_name == b_name && a == b,
+            (Value::NativeFunction(a), Value::NativeFunction(b)) => a.name == b.name,
+            _ => false
+        }
+    }
+}
+
+fn fields_equal(a : &Fields, b : &Fields) -> bool{
+    a.keys.len() == b.keys.len() && a.keys.iter().zip(a.values.iter()).all(|(key, value)| {
+        match b.get(key) {
+            Some(other) => value.equals(&other),
+            None => false
+        }
+    })
+}
+
+fn write_fields(f : &mut fmt::Formatter, fields : &Fields) -> fmt::Result{
+    if fields.keys.is_empty() {
+        return write!(f, "{{}}")
+    }
+
+    write!(f, "{{ ")?;
+    for (index, (key, value)) in fields.keys.iter().zip(fields.values.iter()).enumerate(){
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        match value {
+            Value::Str(string) => write!(f, "{} : {:?}", key, string)?,
+            _ => write!(f, "{} : {}", key, value)?
+        }
+    }
+    write!(f, " }}")
+}
+
+impl fmt::Display for Value{
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Num(number) => write!(f, "{}", number),
+            Value::Str(string) => write!(f, "{}", string),
+            Value::Bool(bool) => write!(f, "{}", bool),
+            Value::Nil => write!(f, "nil"),
+            Value::Array(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.borrow().iter().enumerate(){
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    match value {
+                        Value::Str(string) => write!(f, "{:?}", string)?,
+                        _ => write!(f, "{}", value)?
+                    }
+                }
+                write!(f, "]")
+            },
+            Value::Object(fields) => write_fields(f, &fields.borrow()),
+            Value::Struct { name, fields } => {
+                write!(f, "{} ", name)?;
+                write_fields(f, &fields.borrow())
+            },
+            Value::Variant { name, variant, payload } => {
+                write!(f, "{}::{}", name, variant)?;
+                match payload {
+                    PayloadValue::Unit => Ok(()),
+                    PayloadValue::Tuple(values) => {
+                        write!(f, "(")?;
+                        for (index, value) in values.iter().enumerate(){
+                            if index > 0 {
+                                write!(f, ", ")?;
+                            }
+                            match value {
+                                Value::Str(string) => write!(f, "{:?}", string)?,
+                                _ => write!(f, "{}", value)?
+                            }
+                        }
+                        write!(f, ")")
+                    },
+                    PayloadValue::Fields(fields) => {
+                        write!(f, " ")?;
+                        write_fields(f, &fields.borrow())
+                    }
+                }
+            },
+            Value::Closure(closure) => write!(f, "<fn {}>", closure.name.clone().unwrap_or("anonymous".to_string())),
+            Value::NativeFunction(native) => write!(f, "<native fn {}>", native.name),
+            Value::Constructor { name, variant, .. } => write!(f, "<fn {}::{}>", name, variant)
+        }
+    }
+}
+
+//an overload registered with the overload keyword, picked when the operands match the argument types
+#[derive(Clone, Debug)]
+pub struct Overload{
+    operation : TokenType,
+    arguments : Vec<Type>,
+    function : Rc<Closure>
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Environment{
+    values : HashMap<String, Value>,
+    //names declared with const, these cannot be reassigned
+    constants : HashSet<String>,
+
+    enclosing : Option<Rc<RefCell<Environment>>>,
+
+    //just like in the type environment overloads are not global but live inside of scopes
+    operations : Vec<Overload>,
+
+    //the functions of impl blocks by (struct, function)
+    methods : HashMap<(String, String), Rc<Closure>>,
+
+    //the variants of enums by (enum, variant)
+    variants : HashMap<(String, String), Payload>,
+
+    //what the types declared with type stand for, is and overloads check values against those
+    aliases : HashMap<String, (Vec<String>, Type)>
+}
+
+impl Environment{
+    //unlike the type environment we share the enclosing environment instead of copying it, otherwise
+    //assignments inside of blocks and closures would never reach the outer scope
+    pub fn enclose(enclosing : Rc<RefCell<Environment>>) -> Environment{
+        Self{
+            enclosing : Some(enclosing),
+            ..Default::default()
+        }
+    }
+
+    pub fn new() -> Self{
+        Self{
+            ..Default::default()
+        }
+    }
+
+    //forgets everything declared in this scope, which drops the functions that keep it alive
+    pub fn clear(&mut self){
+        *self = Self{
+            enclosing : self.enclosing.take(),
+            ..Default::default()
+        };
+    }
+
+    //moves everything declared in this scope into the other one. closures created in here keep holding
+    //this scope, but once it is empty they find the names in the other one
+    pub fn move_into(&mut self, other : &mut Environment){
+        for (key, value) in self.values.drain(){
+            if self.constants.contains(&key) {
+                other.constants.insert(key.clone());
+            } else {
+                other.constants.remove(&key);
+            }
+            other.values.insert(key, value);
+        }
+        self.constants.clear();
+
+        other.operations.append(&mut self.operations);
+        other.methods.extend(self.methods.drain());
+        other.variants.extend(self.variants.drain());
+        other.aliases.extend(self.aliases.drain());
+    }
+
+    //the environment every program starts in, holds the builtin functions
+    pub fn global() -> Self{
+        let mut env = Self::new();
+
+        for native in natives(){
+            env.values.insert(native.name.to_string(), Value::NativeFunction(native));
+        }
+
+        env
+    }
+
+    //the span is where the name was used, so a missing name can be reported there
+    pub fn get(&self, key : &str, span : Span) -> Result<Value, Error>{
+        if let Some(value) = self.values.get(key){
+            return Ok(value.clone())
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            return enclosing.borrow().get(key, span)
+        }
+
+        Err(Error::UndefinedVariable{
+            name : key.to_string(),
+            span
+        })
+    }
+
+    //declarations always happen in the current scope and may shadow outer ones
+    pub fn define(&mut self, key : String, value : Value, constant : bool){
+        if constant {
+            self.constants.insert(key.clone());
+        } else {
+            self.constants.remove(&key);
+        }
+
+        self.values.insert(key, value);
+    }
+
+    //assignments walk up the scopes until they find the declaration
+    pub fn assign(&mut self, key : String, value : Value, span : Span) -> Result<(), Error>{
+        if self.values.contains_key(&key){
+            if self.constants.contains(&key){
+                return Err(Error::ConstantReassignment{
+                    name : key,
+                    span
+                })
+            }
+
+            self.values.insert(key, value);
+            return Ok(())
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            return enclosing.borrow_mut().assign(key, value, span)
+        }
+
+        Err(Error::UndefinedVariable{
+            name : key,
+            span
+        })
+    }
+
+    pub fn define_variant(&mut self, enum_name : String, variant : String, payload : Payload){
+        self.variants.insert((enum_name, variant), payload);
+    }
+
+    pub fn get_variant(&self, enum_name : &str, variant : &str) -> Option<Payload>{
+        if let Some(payload) = self.variants.get(&(enum_name.to_string(), variant.to_string())){
+            return Some(payload.clone())
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            return enclosing.borrow().get_variant(enum_name, variant)
+        }
+
+        None
+    }
+
+    pub fn define_method(&mut self, struct_name : String, name : String, function : Rc<Closure>){
+        self.methods.insert((struct_name, name), function);
+    }
+
+    pub fn get_method(&self, struct_name : &str, name : &str) -> Option<Rc<Closure>>{
+        if let Some(function) = self.methods.get(&(struct_name.to_string(), name.to_string())){
+            return Some(function.clone())
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            return enclosing.borrow().get_method(struct_name, name)
+        }
+
+        None
+    }
+
+    pub fn define_alias(&mut self, name : String, generics : Vec<String>, r#type : Type){
+        self.aliases.insert(name, (generics, r#type));
+    }
+
+    //the generics and the type of an alias
+    pub fn get_alias(&self, name : &str) -> Option<(Vec<String>, Type)>{
+        if let Some(alias) = self.aliases.get(name){
+            return Some(alias.clone())
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            return enclosing.borrow().get_alias(name)
+        }
+
+        None
+    }
+
+    pub fn overload(&mut self, overload : Overload){
+        self.operations.push(overload);
+    }
+
+    //looks for an overload of the operation that takes exactly these operands
+    pub fn get_overload(&self, operation : &TokenType, operands : &[Value]) -> Option<Rc<Closure>>{
+        let found = self.operations.iter().rev().find(|overload| {
+            overload.operation == *operation
+                && overload.arguments.len() == operands.len()
+                && overload.arguments.iter().zip(operands.iter()).all(|(arg_type, value)| value.matches_type(arg_type, self))
+        });
+
+        if let Some(overload) = found{
+            return Some(overload.function.clone())
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            return enclosing.borrow().get_overload(operation, operands)
+        }
+
+        None
+    }
+}
+
+fn natives() -> Vec<NativeFunction>{
+    vec![
+        NativeFunction{
+            name : "print",
+            arity : None,
+            function : |arguments, _| {
+                let printed = arguments.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(" ");
+                println!("{}", printed);
+                Ok(Value::Nil)
+            }
+        },
+        NativeFunction{
+            name : "len",
+            arity : Some(1),
+            function : |arguments, span| {
+                match &arguments[0] {
+                    Value::Str(string) => Ok(Value::Num(string.chars().count() as f64)),
+                    Value::Array(values) => Ok(Value::Num(values.borrow().len() as f64)),
+                    Value::Object(fields) => Ok(Value::Num(fields.borrow().keys.len() as f64)),
+                    other => Err(Error::NotIterable{
+                        found : other.type_name(),
+                        span
+                    })
+                }
+            }
+        },
+        NativeFunction{
+            name : "type",
+            arity : Some(1),
+            function : |arguments, _| Ok(Value::Str(arguments[0].type_name()))
+        },
+        NativeFunction{
+            name : "push",
+            arity : Some(2),
+            function : |arguments, span| {
+                match &arguments[0] {
+                    Value::Array(values) => {
+                        values.borrow_mut().push(arguments[1].clone());
+                        Ok(Value::Nil)
+                    },
+                    other => Err(Error::NotIterable{
+                        found : other.type_name(),
+                        span
+                    })
+                }
+            }
+        },
+    ]
+}
+
+//return, break and continue unwind the evaluation until they hit the function or loop that handles
+//them, errors unwind all the way up, the spans point to the keyword in case nothing handles them
+enum Interrupt{
+    Error(Error),
+    Return(Value, Span),
+    Break(Span),
+    Continue(Span)
+}
+
+impl Interrupt{
+    //what an interrupt becomes once it escapes the construct that should have handled it
+    fn into_error(self) -> Error{
+        let (keyword, span) = match self {
+            Interrupt::Error(error) => return error,
+            Interrupt::Return(_, span) => ("return", span),
+            Interrupt::Break(span) => ("break", span),
+            Interrupt::Continue(span) => ("continue", span),
+        };
+
+        Error::InvalidControlFlow{
+            keyword : keyword.to_string(),
+            span
+        }
+    }
+}
+
+impl From<Error> for Interrupt{
+    fn from(error : Error) -> Self {
+        Interrupt::Error(error)
+    }
+}
+
+type Evaluation = Result<Value, Interrupt>;
+
+//how deeply calls can be nested before the program is stopped. every call takes up some of the rust
+//stack, main gives the interpreter a stack large enough for this many
+pub const MAX_CALL_DEPTH : usize = 1000;
+
+thread_local!{
+    //the number of calls that have not returned yet
+    static CALL_DEPTH : Cell<usize> = const { Cell::new(0) };
+}
+
+pub fn interpret(ast : Vec<Expression>) -> Result<Value, Error>{
+    let global_env = Rc::new(RefCell::new(Environment::global()));
+
+    evaluate(ast, global_env)
+}
+
+//evaluates a list of expressions inside of the given environment and returns the value of the last
+//one, the environment is kept around so it can be reused (for example by a repl)
+pub fn evaluate(ast : Vec<Expression>, environment : Rc<RefCell<Environment>>) -> Result<Value, Error>{
+    evaluate_expressions(&ast, &environment).map_err(Interrupt::into_error)
+}
+
+fn evaluate_expressions(expressions : &[Expression], environment : &Rc<RefCell<Environment>>) -> Evaluation{
+    let mut last = Value::Nil;
+
+    for expression in expressions{
+        last = evaluate_expression(expression, environment)?;
+    }
+
+    Ok(last)
+}
+
+fn evaluate_expression(expression : &Expression, environment : &Rc<RefCell<Environment>>) -> Evaluation{
+    let span = expression.span;
+
+    match &expression.kind {
+        ExpressionKind::LiteralNum(number) => Ok(Value::Num(number.value)),
+        ExpressionKind::LiteralStr(string) => Ok(Value::Str(string.to_string())),
+        ExpressionKind::Interpolated(parts) => {
+            let mut string = String::new();
+            for part in parts{
+                string += &evaluate_expression(part, environment)?.to_string();
+            }
+            Ok(Value::Str(string))
+        },
+        ExpressionKind::LiteralBool(bool) => Ok(Value::Bool(*bool)),
+        ExpressionKind::LiteralNil => Ok(Value::Nil),
+        ExpressionKind::LiteralID(name) => Ok(environment.borrow().get(name, span)?),
+
+        ExpressionKind::LiteralArray(expressions) => {
+            let mut values = Vec::new();
+            for expression in expressions{
+                values.push(evaluate_expression(expression, environment)?);
+            }
+
+            Ok(Value::Array(Rc::new(RefCell::new(values))))
+        },
+        ExpressionKind::LiteralObject(entries) => {
+            let mut fields = Fields::default();
+            for entry in entries{
+                match entry {
+                    ObjectEntry::Field { key, value } => {
+                        let key = match &key.r#type {
+                            TokenType::ID(name) | TokenType::STR(name) => name.to_string(),
+                            other => other.to_string()
+                        };
+
+                        fields.set(key, evaluate_expression(value, environment)?);
+                    },
+                    ObjectEntry::Spread { operator, value } => {
+                        match evaluate_expression(value, environment)? {
+                            Value::Object(spread) | Value::Struct { fields : spread, .. } => {
+                                let spread = spread.borrow();
+                                for (key, value) in spread.keys.iter().zip(spread.values.iter()){
+                                    fields.set(key.to_string(), value.clone());
+                                }
+                            },
+                            other => return Err(Error::InvalidOperands{
+                                operator : operator.clone(),
+                                operands : vec![other.type_name()]
+                            }.into())
+                        }
+                    }
+                }
+            }
+
+            Ok(Value::Object(Rc::new(RefCell::new(fields))))
+        },
+
+        ExpressionKind::Grp { inner } => evaluate_expression(inner, environment),
+
+        ExpressionKind::Block { expressions } => {
+            let env = Rc::new(RefCell::new(Environment::enclose(environment.clone())));
+
+            evaluate_expressions(expressions, &env)
+        },
+
+        ExpressionKind::Unary { operator, right } => {
+            let right = evaluate_expression(right, environment)?;
+
+            match (&operator.r#type, &right) {
+                (TokenType::BANG, Value::Bool(bool)) => Ok(Value::Bool(!bool)),
+                (TokenType::MINUS, Value::Num(number)) => Ok(Value::Num(-number)),
+                _ => call_overload(operator, vec![right], environment)
+            }
+        },
+
+        ExpressionKind::Is { value, r#type } => {
+            let value = evaluate_expression(value, environment)?;
+
+            Ok(Value::Bool(value.matches_type(r#type, &environment.borrow())))
+        },
+
+        ExpressionKind::Binary { left, operator, right } => {
+            match operator.r#type {
+                TokenType::PLUSEQ | TokenType::MINUSEQ | TokenType::STAREQ | TokenType::SLASHEQ => {
+                    compound_assign(left, operator, right, environment)
+                },
+                //and and or short circuit, so the right side is only evaluated if needed
+                TokenType::AND | TokenType::OR => {
+                    let left = evaluate_expression(left, environment)?;
+
+                    match (&operator.r#type, &left) {
+                        (TokenType::AND, Value::Bool(false)) => return Ok(Value::Bool(false)),
+                        (TokenType::OR, Value::Bool(true)) => return Ok(Value::Bool(true)),
+                        _ => ()
+                    }
+
+                    let right = evaluate_expression(right, environment)?;
+                    binary_operation(operator, left, right, environment)
+                },
+                _ => {
+                    let left = evaluate_expression(left, environment)?;
+                    let right = evaluate_expression(right, environment)?;
+                    binary_operation(operator, left, right, environment)
+                }
+            }
+        },
+
+        ExpressionKind::Assign { target, value } => assign(target, value, environment),
+
+        ExpressionKind::Declaration { name, value, constant } => {
+            let value = evaluate_expression(value, environment)?;
+            let key = name.name.r#type.get_id_val().unwrap_or_default();
+
+            environment.borrow_mut().define(key, value, *constant);
+            Ok(Value::Nil)
+        },
+
+        ExpressionKind::If { condition, if_block, else_if_blocks, else_block } => {
+            if is_true(condition, environment)? {
+                return evaluate_expression(if_block, environment)
+            }
+
+            for (condition, block) in else_if_blocks{
+                if is_true(condition, environment)? {
+                    return evaluate_expression(block, environment)
+                }
+            }
+
+            match else_block {
+                Some(block) => evaluate_expression(block, environment),
+                None => Ok(Value::Nil)
+            }
+        },
+
+        ExpressionKind::Match { value, arms } => {
+            let value = evaluate_expression(value, environment)?;
+
+            for arm in arms{
+                let mut bindings = Vec::new();
+                if !matches(&arm.pattern, &value, &mut bindings, environment)? {
+                    continue;
+                }
+
+                let env = Rc::new(RefCell::new(Environment::enclose(environment.clone())));
+                for (name, bound) in bindings{
+                    env.borrow_mut().define(name, bound, false);
+                }
+
+                if let Some(guard) = &arm.guard{
+                    if !is_true(guard, &env)? {
+                        continue;
+                    }
+                }
+
+                return evaluate_expression(&arm.body, &env)
+            }
+
+            Err(Error::NoMatchingArm{
+                found : value.type_name(),
+                span
+            }.into())
+        },
+
+        ExpressionKind::While { condition, block } => {
+            while is_true(condition, environment)? {
+                match evaluate_expression(block, environment) {
+                    Err(Interrupt::Break(_)) => break,
+                    Err(Interrupt::Continue(_)) => continue,
+                    Err(interrupt) => return Err(interrupt),
+                    Ok(_) => ()
+                }
+            }
+
+            Ok(Value::Nil)
+        },
+
+        ExpressionKind::For { binding, range, block } => {
+            let key = binding.name.r#type.get_id_val().unwrap_or_default();
+            let items = for_items(range, environment)?;
+
+            for item in items{
+                //every iteration gets a fresh scope so closures capture the current binding
+                let env = Rc::new(RefCell::new(Environment::enclose(environment.clone())));
+                env.borrow_mut().define(key.clone(), item, false);
+
+                match evaluate_expression(block, &env) {
+                    Err(Interrupt::Break(_)) => break,
+                    Err(Interrupt::Continue(_)) => continue,
+                    Err(interrupt) => return Err(interrupt),
+                    Ok(_) => ()
+                }
+            }
+
+            Ok(Value::Nil)
+        },
+
+        ExpressionKind::Fn { name, arguments, body, .. } => {
+            let name = name.as_ref().and_then(|x| x.r#type.get_id_val());
+
+            let closure = Value::Closure(Rc::new(Closure{
+                name : name.clone(),
+                arguments : arguments.clone(),
+                body : *body.clone(),
+                environment : environment.clone()
+            }));
+
+            //named functions are declarations, anonymous ones are just values
+            if let Some(name) = name{
+                environment.borrow_mut().define(name, closure.clone(), false);
+            }
+
+            Ok(closure)
+        },
+
+        ExpressionKind::FunctionCall { function, arguments } => {
+            let function = evaluate_expression(function, environment)?;
+
+            let mut values = Vec::new();
+            for argument in arguments{
+                values.push(evaluate_expression(argument, environment)?);
+            }
+
+            Ok(call(&function, values, span)?)
+        },
+
+        ExpressionKind::Overload { operation, arguments, body, .. } => {
+            let function = Rc::new(Closure{
+                name : None,
+                arguments : arguments.clone(),
+                body : *body.clone(),
+                environment : environment.clone()
+            });
+
+            environment.borrow_mut().overload(Overload{
+                operation : operation.r#type.clone(),
+                arguments : arguments.iter().map(|x| x.r#type.clone()).collect(),
+                function
+            });
+
+            Ok(Value::Nil)
+        },
+
+        ExpressionKind::StructUsage { struct_name, variant, fields, values } => {
+            let mut instance = Fields::default();
+
+            for (field, value) in fields.iter().zip(values.iter()){
+                let key = field.r#type.get_id_val().unwrap_or_default();
+                instance.set(key, evaluate_expression(value, environment)?);
+            }
+
+            let name = struct_name.r#type.get_id_val().unwrap_or_default();
+            let fields = Rc::new(RefCell::new(instance));
+
+            match variant {
+                Some(variant) => Ok(Value::Variant{
+                    name,
+                    variant : variant.r#type.get_id_val().unwrap_or_default(),
+                    payload : PayloadValue::Fields(fields)
+                }),
+                None => Ok(Value::Struct{
+                    name,
+                    fields
+                })
+            }
+        },
+
+        //types mostly matter to the type checker, but variants have to be known to build them and aliases
+        //to check values against them
+        ExpressionKind::TypeDeclaration { name, generics, r#type, .. } => {
+            environment.borrow_mut().define_alias(name.r#type.get_id_val().unwrap_or_default(), generics.clone(), r#type.clone());
+            Ok(Value::Nil)
+        },
+        ExpressionKind::StructDeclaration { .. } => Ok(Value::Nil),
+        ExpressionKind::EnumDeclaration { name, r#type, .. } => {
+            if let Type::Enum { variants, payloads } = r#type {
+                let key = name.r#type.get_id_val().unwrap_or_default();
+
+                for (variant, payload) in variants.iter().zip(payloads.iter()){
+                    environment.borrow_mut().define_variant(key.clone(), variant.to_string(), payload.clone());
+                }
+            }
+
+            Ok(Value::Nil)
+        },
+
+        ExpressionKind::Return(value) => {
+            let value = evaluate_expression(value, environment)?;
+            Err(Interrupt::Return(value, span))
+        },
+        ExpressionKind::Break => Err(Interrupt::Break(span)),
+        ExpressionKind::Continue => Err(Interrupt::Continue(span)),
+        //the parser already reported these, but a partial tree should never run past them
+        ExpressionKind::Error(error) => Err(Interrupt::Error(error.clone())),
+
+        ExpressionKind::FieldAccess { target, field } => {
+            let target = evaluate_expression(target, environment)?;
+            let name = field.r#type.get_id_val().unwrap_or_default();
+
+            Ok(get_field(&target, &name, field.span)?)
+        },
+        ExpressionKind::Index { target, index } => {
+            let target = evaluate_expression(target, environment)?;
+            let index = evaluate_expression(index, environment)?;
+
+            Ok(get_index(&target, &index, span)?)
+        },
+        //methods of the struct come first, otherwise it is a field that holds a function
+        ExpressionKind::MethodCall { target, method, arguments } => {
+            let target = evaluate_expression(target, environment)?;
+            let name = method.r#type.get_id_val().unwrap_or_default();
+
+            let mut values = Vec::new();
+
+            let function = match &target {
+                Value::Struct { name : struct_name, .. } | Value::Variant { name : struct_name, .. } => match environment.borrow().get_method(struct_name, &name) {
+                    Some(function) => {
+                        if !function.arguments.first().map(|x| x.is_self()).unwrap_or(false) {
+                            return Err(Error::NotAMethod{
+                                struct_name : struct_name.to_string(),
+                                function : name,
+                                span : method.span
+                            }.into())
+                        }
+
+                        values.push(target.clone());
+                        Value::Closure(function)
+                    },
+                    None => get_field(&target, &name, method.span).map_err(|_| Error::MethodNotFound{
+                        struct_name : struct_name.to_string(),
+                        method : name.clone(),
+                        span : method.span
+                    })?
+                },
+                _ => get_field(&target, &name, method.span)?
+            };
+
+            for argument in arguments{
+                values.push(evaluate_expression(argument, environment)?);
+            }
+
+            Ok(call(&function, values, span)?)
+        },
+
+        ExpressionKind::Associated { struct_name, function } => {
+            let key = struct_name.r#type.get_id_val().unwrap_or_default();
+            let name = function.r#type.get_id_val().unwrap_or_default();
+            let path = format!("{}::{}", key, name);
+
+            //variants come before the functions of impl blocks
+            match environment.borrow().get_variant(&key, &name) {
+                Some(Payload::Unit) => return Ok(Value::Variant{
+                    name : key,
+                    variant : name,
+                    payload : PayloadValue::Unit
+                }),
+                Some(Payload::Tuple(types)) => return Ok(Value::Constructor{
+                    name : key,
+                    variant : name,
+                    arity : types.len()
+                }),
+                Some(payload) => return Err(Error::PayloadMismatch{
+                    usage : payload.usage(&path),
+                    variant : path,
+                    span : function.span
+                }.into()),
+                None => ()
+            }
+
+            match environment.borrow().get_method(&key, &name) {
+                Some(found) => Ok(Value::Closure(found)),
+                None => Err(Error::MethodNotFound{
+                    struct_name : key,
+                    method : name,
+                    span : function.span
+                }.into())
+            }
+        },
+
+        ExpressionKind::Impl { struct_name, functions } => {
+            let key = struct_name.r#type.get_id_val().unwrap_or_default();
+
+            for function in functions{
+                if let ExpressionKind::Fn { name : Some(name), arguments, body, .. } = &function.kind {
+                    let name = name.r#type.get_id_val().unwrap_or_default();
+
+                    environment.borrow_mut().define_method(key.clone(), name.clone(), Rc::new(Closure{
+                        name : Some(format!("{}::{}", key, name)),
+                        arguments : arguments.clone(),
+                        body : *body.clone(),
+                        environment : environment.clone()
+                    }));
+                }
+            }
+
+            Ok(Value::Nil)
+        }
+    }
+}
+
+//checks if the value fits the pattern and collects what the pattern binds
+fn matches(pattern : &Pattern, value : &Value, bindings : &mut Vec<(String, Value)>, environment : &Rc<RefCell<Environment>>) -> Result<bool, Interrupt>{
+    match (&pattern.kind, value) {
+        (PatternKind::Wildcard, _) => Ok(true),
+        (PatternKind::Binding(name), _) => {
+            bindings.push((name.to_string(), value.clone()));
+            Ok(true)
+        },
+        (PatternKind::Literal(literal), _) => Ok(evaluate_expression(literal, environment)?.equals(value)),
+        (PatternKind::Typed { name, r#type }, _) => {
+            if !value.matches_type(r#type, &environment.borrow()) {
+                return Ok(false)
+            }
+
+            if let Some(name) = name{
+                bindings.push((name.to_string(), value.clone()));
+            }
+            Ok(true)
+        },
+        (PatternKind::Struct { name, fields : patterns }, Value::Struct { name : struct_name, fields }) => {
+            if name.r#type.get_id_val().as_ref() != Some(struct_name) {
+                return Ok(false)
+            }
+
+            matches_fields(patterns, &fields.borrow(), bindings, environment)
+        },
+        (PatternKind::Object(patterns), Value::Object(fields) | Value::Struct { fields, .. }) => {
+            matches_fields(patterns, &fields.borrow(), bindings, environment)
+        },
+        (PatternKind::Array { elements, rest }, Value::Array(values)) => {
+            let values = values.borrow();
+
+            let fits = match rest {
+                Some(_) => values.len() >= elements.len(),
+                None => values.len() == elements.len()
+            };
+            if !fits {
+                return Ok(false)
+            }
+
+            for (element, value) in elements.iter().zip(values.iter()){
+                if !matches(element, value, bindings, environment)? {
+                    return Ok(false)
+                }
+            }
+
+            match rest {
+                Some(rest) => {
+                    let rest_values = Value::Array(Rc::new(RefCell::new(values[elements.len()..].to_vec())));
+                    matches(rest, &rest_values, bindings, environment)
+                },
+                None => Ok(true)
+            }
+        },
+        (PatternKind::Variant { enum_name, variant, payload }, Value::Variant { name, variant : value_variant, payload : value_payload }) => {
+            if enum_name.r#type.get_id_val().as_ref() != Some(name) || variant.r#type.get_id_val().as_ref() != Some(value_variant) {
+                return Ok(false)
+            }
+
+            match (payload, value_payload) {
+                (PatternPayload::Unit, PayloadValue::Unit) => Ok(true),
+                (PatternPayload::Tuple(patterns), PayloadValue::Tuple(values)) if patterns.len() == values.len() => {
+                    for (pattern, value) in patterns.iter().zip(values.iter()){
+                        if !matches(pattern, value, bindings, environment)? {
+                            return Ok(false)
+                        }
+                    }
+                    Ok(true)
+                },
+                (PatternPayload::Fields(patterns), PayloadValue::Fields(fields)) => matches_fields(patterns, &fields.borrow(), bindings, environment),
+                _ => Ok(false)
+            }
+        },
+        _ => Ok(false)
+    }
+}
+
+fn matches_fields(patterns : &[(Token, Pattern)], fields : &Fields, bindings : &mut Vec<(String, Value)>, environment : &Rc<RefCell<Environment>>) -> Result<bool, Interrupt>{
+    for (key, pattern) in patterns{
+        let field = match fields.get(&key.r#type.get_id_val().unwrap_or_default()) {
+            Some(field) => field,
+            None => return Ok(false)
+        };
+
+        if !matches(pattern, &field, bindings, environment)? {
+            return Ok(false)
+        }
+    }
+
+    Ok(true)
+}
+
+fn is_true(condition : &Expression, environment : &Rc<RefCell<Environment>>) -> Result<bool, Interrupt>{
+    match evaluate_expression(condition, environment)? {
+        Value::Bool(bool) => Ok(bool),
+        other => Err(Error::InvalidCondition{
+            found : other.type_name(),
+            span : condition.span
+        }.into())
+    }
+}
+
+//everything a for loop walks over, numeric ranges are worked out one value at a time since they can be huge
+fn for_items(range : &ForRange, environment : &Rc<RefCell<Environment>>) -> Result<Box<dyn Iterator<Item = Value>>, Interrupt>{
+    match range {
+        ForRange::In(iterable) => {
+            let items : Vec<Value> = match evaluate_expression(iterable, environment)? {
+                Value::Array(values) => values.borrow().clone(),
+                Value::Str(string) => string.chars().map(|x| Value::Str(x.to_string())).collect(),
+                Value::Object(fields) | Value::Struct { fields, .. } => {
+                    fields.borrow().keys.iter().map(|x| Value::Str(x.to_string())).collect()
+                },
+                other => return Err(Error::NotIterable{
+                    found : other.type_name(),
+                    span : iterable.span
+                }.into())
+            };
+
+            Ok(Box::new(items.into_iter()))
+        },
+        //numeric ranges include their end, just like you would read "for i = 1 to 10"
+        ForRange::To { start, end, step } => {
+            let start = expect_num(start, environment)?;
+            let end = expect_num(end, environment)?;
+            let step = match step {
+                Some(step) => {
+                    let amount = expect_num(step, environment)?;
+
+                    if amount == 0.0 {
+                        return Err(Error::ZeroStep(step.span).into())
+                    }
+
+                    amount
+                },
+                None => 1.0
+            };
+
+            //every value comes from its index so rounding errors of the step do not add up
+            let items = (0u64..)
+                .map(move |index| start + step * index as f64)
+                .take_while(move |current| (step > 0.0 && *current <= end) || (step < 0.0 && *current >= end))
+                .map(Value::Num);
+
+            Ok(Box::new(items))
+        }
+    }
+}
+
+fn expect_num(expression : &Expression, environment : &Rc<RefCell<Environment>>) -> Result<f64, Interrupt>{
+    match evaluate_expression(expression, environment)? {
+        Value::Num(number) => Ok(number),
+        other => Err(Error::InvalidBound{
+            found : other.type_name(),
+            span : expression.span
+        }.into())
+    }
+}
+
+fn assign(target : &Expression, value : &Expression, environment : &Rc<RefCell<Environment>>) -> Evaluation{
+    let value = evaluate_expression(value, environment)?;
+
+    place(target, environment)?.store(value, environment)
+}
+
+//a += b is a = a + b, the + goes through overloads like any other. the target is only evaluated once, so
+//a[f()] += 1 calls f once
+fn compound_assign(target : &Expression, operator : &Token, value : &Expression, environment : &Rc<RefCell<Environment>>) -> Evaluation{
+    let place = place(target, environment)?;
+    let current = place.load(environment)?;
+    let value = evaluate_expression(value, environment)?;
+
+    let applied = Token{
+        r#type : operator.r#type.compound_operator().unwrap_or(operator.r#type.clone()),
+        span : operator.span
+    };
+    let result = binary_operation(&applied, current, value, environment)?;
+
+    place.store(result, environment)
+}
+
+//where the target of an assignment points, with everything in it evaluated already
+enum Place{
+    Variable(String, Span),
+    Field(Value, Token),
+    Index(Value, Value, Span)
+}
+
+fn place(target : &Expression, environment : &Rc<RefCell<Environment>>) -> Result<Place, Interrupt>{
+    match &target.kind {
+        ExpressionKind::LiteralID(name) => Ok(Place::Variable(name.to_string(), target.span)),
+        ExpressionKind::FieldAccess { target : object, field } => {
+            Ok(Place::Field(evaluate_expression(object, environment)?, field.clone()))
+        },
+        ExpressionKind::Index { target : indexed, index } => {
+            let indexed = evaluate_expression(indexed, environment)?;
+            let index = evaluate_expression(index, environment)?;
+
+            Ok(Place::Index(indexed, index, target.span))
+        },
+        _ => Err(Error::InvalidAssignmentTarget(target.span).into())
+    }
+}
+
+impl Place{
+    fn load(&self, environment : &Rc<RefCell<Environment>>) -> Evaluation{
+        let value = match self {
+            Place::Variable(name, span) => environment.borrow().get(name, *span)?,
+            Place::Field(object, field) => get_field(object, &field.r#type.get_id_val().unwrap_or_default(), field.span)?,
+            Place::Index(indexed, index, span) => get_index(indexed, index, *span)?
+        };
+
+        Ok(value)
+    }
+
+    //puts the value where the place points
+    fn store(self, value : Value, environment : &Rc<RefCell<Environment>>) -> Evaluation{
+        match self {
+            Place::Variable(name, span) => environment.borrow_mut().assign(name, value.clone(), span)?,
+            Place::Field(object, field) => {
+                let name = field.r#type.get_id_val().unwrap_or_default();
+
+                match object {
+                    Value::Object(fields) => fields.borrow_mut().set(name, value.clone()),
+                    //structs keep the fields they were declared with
+                    Value::Struct { fields, .. } if fields.borrow().get(&name).is_some() => fields.borrow_mut().set(name, value.clone()),
+                    _ => return Err(Error::FieldNotFound{
+                        field : name,
+                        span : field.span
+                    }.into())
+                }
+            },
+            Place::Index(indexed, index, span) => {
+                match (&indexed, &index) {
+                    (Value::Array(values), Value::Num(number)) => {
+                        let mut values = values.borrow_mut();
+                        let position = *number as i64;
+
+                        if position < 0 || position as usize >= values.len() || number.fract() != 0.0 {
+                            return Err(Error::IndexOutOfBounds{
+                                index : position,
+                                length : values.len(),
+                                span
+                            }.into())
+                        }
+
+                        values[position as usize] = value.clone();
+                    },
+                    (Value::Object(fields), Value::Str(key)) => fields.borrow_mut().set(key.to_string(), value.clone()),
+                    (Value::Struct { fields, .. }, Value::Str(key)) if fields.borrow().get(key).is_some() => {
+                        fields.borrow_mut().set(key.to_string(), value.clone())
+                    },
+                    _ => return Err(Error::InvalidOperands{
+                        operator : TokenType::LBRACK.spanned(span),
+                        operands : vec![indexed.type_name(), index.type_name()]
+                    }.into())
+                }
+            }
+        }
+
+        Ok(value)
+    }
+}
+
+fn get_field(target : &Value, field : &str, span : Span) -> Result<Value, Error>{
+    let found = match target {
+        Value::Object(fields) | Value::Struct { fields, .. } => fields.borrow().get(field),
+        _ => None
+    };
+
+    found.ok_or(Error::FieldNotFound{
+        field : field.to_string(),
+        span
+    })
+}
+
+fn get_index(target : &Value, index : &Value, span : Span) -> Result<Value, Error>{
+    match (target, index) {
+        (Value::Array(values), Value::Num(number)) => {
+            let values = values.borrow();
+            let position = *number as i64;
+
+            if position < 0 || position as usize >= values.len() || number.fract() != 0.0 {
+                return Err(Error::IndexOutOfBounds{
+                    index : position,
+                    length : values.len(),
+                    span
+                })
+            }
+
+            Ok(values[position as usize].clone())
+        },
+        (Value::Str(string), Value::Num(number)) => {
+            let length = string.chars().count();
+            let position = *number as i64;
+
+            match string.chars().nth(position as usize) {
+                Some(char) if position >= 0 => Ok(Value::Str(char.to_string())),
+                _ => Err(Error::IndexOutOfBounds{
+                    index : position,
+                    length,
+                    span
+                })
+            }
+        },
+        (Value::Object(_) | Value::Struct { .. }, Value::Str(key)) => get_field(target, key, span),
+        _ => Err(Error::InvalidOperands{
+            operator : TokenType::LBRACK.spanned(span),
+            operands : vec![target.type_name(), index.type_name()]
+        })
+    }
+}
+
+fn binary_operation(operator : &Token, left : Value, right : Value, environment : &Rc<RefCell<Environment>>) -> Evaluation{
+    let result = match (&operator.r#type, &left, &right) {
+        (TokenType::PLUS, Value::Num(a), Value::Num(b)) => Value::Num(a + b),
+        (TokenType::MINUS, Value::Num(a), Value::Num(b)) => Value::Num(a - b),
+        (TokenType::STAR, Value::Num(a), Value::Num(b)) => Value::Num(a * b),
+        (TokenType::SLASH, Value::Num(a), Value::Num(b)) => {
+            if *b == 0.0 {
+                return Err(Error::DivisionByZero(operator.clone()).into())
+            }
+            Value::Num(a / b)
+        },
+        (TokenType::PERCENT, Value::Num(a), Value::Num(b)) => {
+            if *b == 0.0 {
+                return Err(Error::DivisionByZero(operator.clone()).into())
+            }
+            Value::Num(a % b)
+        },
+        (TokenType::STARSTAR, Value::Num(a), Value::Num(b)) => Value::Num(a.powf(*b)),
+
+        //bitwise operators work on the integer part of the numbers
+        (TokenType::AMP, Value::Num(a), Value::Num(b)) => Value::Num(((*a as i64) & (*b as i64)) as f64),
+        (TokenType::BAR, Value::Num(a), Value::Num(b)) => Value::Num(((*a as i64) | (*b as i64)) as f64),
+        (TokenType::CARET, Value::Num(a), Value::Num(b)) => Value::Num(((*a as i64) ^ (*b as i64)) as f64),
+        //shifting by 64 or more moves every bit out
+        (TokenType::SHL, Value::Num(a), Value::Num(b)) => Value::Num((*a as i64).checked_shl(*b as u32).unwrap_or(0) as f64),
+        (TokenType::SHR, Value::Num(a), Value::Num(b)) => {
+            let a = *a as i64;
+            Value::Num(a.checked_shr(*b as u32).unwrap_or(if a < 0 { -1 } else { 0 }) as f64)
+        },
+
+        (TokenType::PLUS, Value::Str(a), Value::Str(b)) => Value::Str(a.to_string() + b),
+
+        (TokenType::GE, Value::Num(a), Value::Num(b)) => Value::Bool(a > b),
+        (TokenType::GEQ, Value::Num(a), Value::Num(b)) => Value::Bool(a >= b),
+        (TokenType::LE, Value::Num(a), Value::Num(b)) => Value::Bool(a < b),
+        (TokenType::LEQ, Value::Num(a), Value::Num(b)) => Value::Bool(a <= b),
+
+        (TokenType::AND, Value::Bool(a), Value::Bool(b)) => Value::Bool(*a && *b),
+        (TokenType::OR, Value::Bool(a), Value::Bool(b)) => Value::Bool(*a || *b),
+        (TokenType::XOR, Value::Bool(a), Value::Bool(b)) => Value::Bool(a ^ b),
+
+        //overloads of == and != take precedence over the structural equality
+        (TokenType::EQEQ, _, _) | (TokenType::NEQ, _, _) => {
+            let operands = vec![left.clone(), right.clone()];
+            if let Some(function) = environment.borrow().get_overload(&operator.r#type, &operands){
+                return Ok(call(&Value::Closure(function), operands, operator.span)?)
+            }
+
+            let equal = left.equals(&right);
+            Value::Bool(if operator.r#type == TokenType::EQEQ { equal } else { !equal })
+        },
+
+        _ => return call_overload(operator, vec![left, right], environment)
+    };
+
+    Ok(result)
+}
+
+fn call_overload(operator : &Token, operands : Vec<Value>, environment : &Rc<RefCell<Environment>>) -> Evaluation{
+    let function = environment.borrow().get_overload(&operator.r#type, &operands);
+
+    match function {
+        Some(function) => Ok(call(&Value::Closure(function), operands, operator.span)?),
+        None => Err(Error::InvalidOperands{
+            operator : operator.clone(),
+            operands : operands.iter().map(|x| x.type_name()).collect()
+        }.into())
+    }
+}
+
+//calls a function value, the span is the call site and is used for errors about the call itself
+pub fn call(function : &Value, arguments : Vec<Value>, span : Span) -> Result<Value, Error>{
+    match function {
+        Value::Closure(closure) => {
+            if closure.arguments.len() != arguments.len(){
+                return Err(Error::ArityMismatch{
+                    expected : closure.arguments.len(),
+                    found : arguments.len(),
+                    span
+                })
+            }
+
+            let depth = CALL_DEPTH.with(|depth| depth.get());
+            if depth >= MAX_CALL_DEPTH {
+                return Err(Error::CallDepthExceeded{
+                    limit : MAX_CALL_DEPTH,
+                    span
+                })
+            }
+
+            let mut env = Environment::enclose(closure.environment.clone());
+            for (argument, value) in closure.arguments.iter().zip(arguments){
+                let key = argument.name.r#type.get_id_val().unwrap_or_default();
+                env.define(key, value, false);
+            }
+
+            CALL_DEPTH.with(|x| x.set(depth + 1));
+            let result = evaluate_expression(&closure.body, &Rc::new(RefCell::new(env)));
+            CALL_DEPTH.with(|x| x.set(depth));
+
+            match result {
+                Ok(value) => Ok(value),
+                Err(Interrupt::Return(value, _)) => Ok(value),
+                Err(interrupt) => Err(interrupt.into_error())
+            }
+        },
+        Value::NativeFunction(native) => {
+            if let Some(arity) = native.arity{
+                if arity != arguments.len(){
+                    return Err(Error::ArityMismatch{
+                        expected : arity,
+                        found : arguments.len(),
+                        span
+                    })
+                }
+            }
+
+            (native.function)(arguments, span)
+        },
+        Value::Constructor { name, variant, arity } => {
+            if *arity != arguments.len(){
+                return Err(Error::ArityMismatch{
+                    expected : *arity,
+                    found : arguments.len(),
+                    span
+                })
+            }
+
+            Ok(Value::Variant{
+                name : name.to_string(),
+                variant : variant.to_string(),
+                payload : PayloadValue::Tuple(arguments)
+            })
+        },
+        other => Err(Error::NotCallable{
+            found : other.type_name(),
+            span
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    //runs the program and returns the value of its last expression, printed
+    fn run(text : &'static str) -> Result<String, Error>{
+        interpret(parse(lex(text).unwrap()).unwrap()).map(|value| value.to_string())
+    }
+
+    #[test]
+    fn declarations_and_assignments(){
+        assert_eq!(run("let a = 1; const b = 2; a = a + b; a;").unwrap(), "3");
+        assert!(run("const b = 2; b = 3;").is_err());
+        assert!(run("c;").is_err());
+    }
+
+    #[test]
+    fn if_and_while(){
+        assert_eq!(run("let n = 0; while n < 5 { n = n + 1; } if n == 5 { \"five\"; } else { \"other\"; }").unwrap(), "five");
+    }
+
+    #[test]
+    fn for_loops(){
+        assert_eq!(run("let s = 0; for i = 1 to 4 { s = s + i; } s;").unwrap(), "10");
+        assert_eq!(run("let s = 0; for i = 10 to 0 step 0 - 5 { s = s + i; } s;").unwrap(), "15");
+        assert_eq!(run("let s = \"\"; for x in [\"a\", \"b\"] { s = s + x; } s;").unwrap(), "ab");
+    }
+
+    #[test]
+    fn break_and_continue(){
+        assert_eq!(run("let s = 0; for i = 1 to 10 { if i == 2 { continue; } if i == 4 { break; } s = s + i; } s;").unwrap(), "4");
+        assert!(run("break;").is_err());
+    }
+
+    #[test]
+    fn functions_and_closures(){
+        assert_eq!(run("fn fact(n : num) -> num { if n < 1 { return 1; } return n * fact(n - 1); } fact(5);").unwrap(), "120");
+        assert_eq!(run("fn counter() { let c = 0; return fn() { c = c + 1; return c; }; }
+let next = counter(); next(); next();").unwrap(), "2");
+        assert!(run("fn f(a : num) -> num { return a; } f(1, 2);").is_err());
+    }
+
+    #[test]
+    fn interpolation(){
+        assert_eq!(run(r#"let n = 3; "n = ${n + 1}, ${"nested ${n}"} and \${not}";"#).unwrap(), "n = 4, nested 3 and ${not}");
+    }
+
+    #[test]
+    fn cleared_scopes_are_freed(){
+        let environment = Rc::new(RefCell::new(Environment::global()));
+        evaluate(parse(lex("fn f() { } struct P { x : num } impl P { fn g(self) { } } overload + (a : P, b : P) -> P a;").unwrap()).unwrap(), environment.clone()).unwrap();
+
+        assert!(Rc::strong_count(&environment) > 1);
+        environment.borrow_mut().clear();
+        assert_eq!(Rc::strong_count(&environment), 1);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::{FileId, LexError, LexErrorKind};
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum TokenType{
     LPAREN, 
@@ -11,6 +13,13 @@ pub enum TokenType{
     ID(String),
     NUM(String),
     STR(String),
+    //the pieces of a string with interpolations in it, "a ${x} b ${y} c" is lexed into
+    //STRHEAD("a "), x, STRMID(" b "), y, STRTAIL(" c")
+    STRHEAD(String),
+    STRMID(String),
+    STRTAIL(String),
+    //the text of a /// comment, these are kept so they can be attached to declarations
+    DOC(String),
     FALSE,
     TRUE,
     NIL,
@@ -22,15 +31,33 @@ pub enum TokenType{
     STR_,
 
     COLON,
+    //paths to associated functions: vec3::zero
+    COLONCOLON,
     SEMICOLON,
 
     DOT,
+    //the spread in object literals: { ...other }
+    DOTDOTDOT,
     COMMA,
     
     PLUS, 
     MINUS,
     STAR, 
     SLASH,
+    PERCENT,
+    STARSTAR,
+
+    //bitwise operators, | is BAR since it is also used for union types
+    AMP,
+    CARET,
+    SHL,
+    SHR,
+
+    //compound assignments like +=
+    PLUSEQ,
+    MINUSEQ,
+    STAREQ,
+    SLASHEQ,
 
     BANG,
     NEQ,
@@ -44,6 +71,7 @@ pub enum TokenType{
     AND,
     OR,
     XOR,
+    IS,
 
     ARROW, // this thing: "->"
     IMPL, //this thing: "=>"
@@ -54,6 +82,7 @@ pub enum TokenType{
  
     IF,
     ELSE,
+    MATCH,
 
     WHILE,
     FOR,
@@ -61,11 +90,15 @@ pub enum TokenType{
     CONTINUE,
     RETURN,
 
+    IN,
     TO,
+    STEP,
 
     //this is type stuff
     TYPE,
     STRUCT,
+    ENUM,
+    IMPLBLOCK, //the impl keyword, IMPL is the "=>" of match arms
     BAR,
 
     OVERLOAD,
@@ -73,11 +106,65 @@ pub enum TokenType{
     EOF,
 }
 
+//a point inside of the source text, the offset is in bytes, lines and columns start at 0. these are u32
+//since every token and error carries two of them
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Position{
+    pub offset : u32,
+    pub line : u32,
+    pub column : u32
+}
+
+//the range of source text something was made from, the end is exclusive
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Span{
+    pub start : Position,
+    pub end : Position,
+    //the file the text is in, see SourceMap
+    pub file : FileId
+}
+
+impl Span{
+    pub fn new(start : Position, end : Position) -> Self{
+        Self{
+            start,
+            end,
+            file : FileId::default()
+        }
+    }
+
+    //the span covering both self and other (and everything in between)
+    pub fn to(&self, other : Span) -> Span{
+        let start = if self.start.offset <= other.start.offset { self.start } else { other.start };
+        let end = if self.end.offset >= other.end.offset { self.end } else { other.end };
+
+        Span{
+            start,
+            end,
+            file : self.file
+        }
+    }
+}
+
 impl TokenType{
     pub fn token(&self, line : usize, column : usize) -> Token{
+        let position = Position{
+            offset : 0,
+            line : line as u32,
+            column : column as u32
+        };
+
         return Token{
             r#type : self.clone(),
-            position : (line, column)
+            span : Span::new(position, position)
+        }
+    }
+
+    //a token that was not lexed but stands for source that is already known, like the brackets of an index
+    pub fn spanned(&self, span : Span) -> Token{
+        Token{
+            r#type : self.clone(),
+            span
         }
     }
 
@@ -95,6 +182,17 @@ impl TokenType{
         return self.ignore_value() == check_type.ignore_value()
     }
     
+    //the operator a compound assignment applies, so a += b can be done as a = a + b
+    pub fn compound_operator(&self) -> Option<TokenType>{
+        match self {
+            Self::PLUSEQ => Some(Self::PLUS),
+            Self::MINUSEQ => Some(Self::MINUS),
+            Self::STAREQ => Some(Self::STAR),
+            Self::SLASHEQ => Some(Self::SLASH),
+            _ => None
+        }
+    }
+
     pub fn get_id_val(&self) -> Option<String>{
         if let TokenType::ID(str) = self{
             Some(str.to_string())
@@ -104,10 +202,95 @@ impl TokenType{
     }
 }
 
+//how a token type is called in error messages
+impl std::fmt::Display for TokenType{
+    fn fmt(&self, f : &mut std::fmt::Formatter) -> std::fmt::Result {
+        let symbol = match self {
+            TokenType::ID(name) => return write!(f, "`{}`", name),
+            TokenType::NUM(number) => return write!(f, "`{}`", number),
+            TokenType::STR(string) => return write!(f, "{:?}", string),
+            TokenType::STRHEAD(string) => return write!(f, "\"{}${{", string.escape_debug()),
+            TokenType::STRMID(string) => return write!(f, "}}{}${{", string.escape_debug()),
+            TokenType::STRTAIL(string) => return write!(f, "}}{}\"", string.escape_debug()),
+            TokenType::DOC(_) => return write!(f, "a doc comment"),
+            TokenType::ID_ => return write!(f, "a name"),
+            TokenType::NUM_ => return write!(f, "a number"),
+            TokenType::STR_ => return write!(f, "a string"),
+            TokenType::EOF => return write!(f, "the end of the file"),
+
+            TokenType::LPAREN => "(",
+            TokenType::RPAREN => ")",
+            TokenType::LBRACK => "[",
+            TokenType::RBRACK => "]",
+            TokenType::LBRACE => "{",
+            TokenType::RBRACE => "}",
+            TokenType::FALSE => "false",
+            TokenType::TRUE => "true",
+            TokenType::NIL => "nil",
+            TokenType::COLON => ":",
+            TokenType::COLONCOLON => "::",
+            TokenType::SEMICOLON => ";",
+            TokenType::DOT => ".",
+            TokenType::DOTDOTDOT => "...",
+            TokenType::COMMA => ",",
+            TokenType::PLUS => "+",
+            TokenType::MINUS => "-",
+            TokenType::STAR => "*",
+            TokenType::SLASH => "/",
+            TokenType::PERCENT => "%",
+            TokenType::STARSTAR => "**",
+            TokenType::AMP => "&",
+            TokenType::CARET => "^",
+            TokenType::SHL => "<<",
+            TokenType::SHR => ">>",
+            TokenType::PLUSEQ => "+=",
+            TokenType::MINUSEQ => "-=",
+            TokenType::STAREQ => "*=",
+            TokenType::SLASHEQ => "/=",
+            TokenType::BANG => "!",
+            TokenType::NEQ => "!=",
+            TokenType::EQEQ => "==",
+            TokenType::EQ => "=",
+            TokenType::GEQ => ">=",
+            TokenType::GE => ">",
+            TokenType::LEQ => "<=",
+            TokenType::LE => "<",
+            TokenType::AND => "and",
+            TokenType::OR => "or",
+            TokenType::XOR => "xor",
+            TokenType::IS => "is",
+            TokenType::ARROW => "->",
+            TokenType::IMPL => "=>",
+            TokenType::FN => "fn",
+            TokenType::LET => "let",
+            TokenType::CONST => "const",
+            TokenType::IF => "if",
+            TokenType::ELSE => "else",
+            TokenType::MATCH => "match",
+            TokenType::WHILE => "while",
+            TokenType::FOR => "for",
+            TokenType::BREAK => "break",
+            TokenType::CONTINUE => "continue",
+            TokenType::RETURN => "return",
+            TokenType::IN => "in",
+            TokenType::TO => "to",
+            TokenType::STEP => "step",
+            TokenType::TYPE => "type",
+            TokenType::STRUCT => "struct",
+            TokenType::ENUM => "enum",
+            TokenType::IMPLBLOCK => "impl",
+            TokenType::BAR => "|",
+            TokenType::OVERLOAD => "overload",
+        };
+
+        write!(f, "`{}`", symbol)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Token{
     pub r#type : TokenType,
-    pub position : (usize, usize)
+    pub span : Span
 }
 
 impl Token{
@@ -120,76 +303,139 @@ impl Token{
     }
 }
 
-fn number(chars : Vec<String>, index : &mut usize, line : &mut usize, column : &mut usize, tokens : &mut Vec<Token>) -> Result<(), String>{
-    
-    let mut number = "".to_string();
+//consumes digits (and _ separators) as long as they fit the check, returns them without the _
+fn digits(chars : &[String], index : &mut usize, is_digit : fn(char) -> bool) -> String{
+    let mut digits = "".to_string();
+
+    while let Some(char) = chars.get(*index).and_then(|x| x.chars().next()){
+        if char == '_' {
+            *index += 1;
+        } else if is_digit(char) {
+            digits.push(char);
+            *index += 1;
+        } else {
+            break;
+        }
+    }
 
-    let start_position = (line.clone(), column.clone());
+    digits
+}
 
-    let number_match = "_0123456789";
+//numbers are 123, 1_000, 1.5, .5, 6.02e23, 1e-9 or, with a prefix, 0x1F, 0b1010 and 0o755
+fn number(chars : Vec<String>, index : &mut usize, line : &mut usize, column : &mut usize, tokens : &mut Vec<Token>) -> Result<(), LexErrorKind>{
     
-    let mut dot_used = false;
+    let start_position = (*line, *column);
+    let start_index = *index;
+
+    let peek = |index : usize| chars.get(index).and_then(|x| x.chars().next());
+
+    let prefix = match (peek(*index), peek(*index + 1)) {
+        (Some('0'), Some('x')) => Some(("0x", 16, "a hexadecimal")),
+        (Some('0'), Some('b')) => Some(("0b", 2, "a binary")),
+        (Some('0'), Some('o')) => Some(("0o", 8, "an octal")),
+        _ => None
+    };
+
+    let result = match prefix {
+        Some((prefix, radix, name)) => {
+            *index += 2;
+
+            let number = digits(&chars, index, |x| x.is_ascii_hexdigit());
+
+            if let Some(invalid) = number.chars().find(|x| !x.is_digit(radix)) {
+                Err(format!("`{}` is not {} digit", invalid, name))
+            } else if number.is_empty() {
+                Err(format!("expected digits after `{}`", prefix))
+            } else {
+                Ok(format!("{}{}", prefix, number))
+            }
+        },
+        None => {
+            let mut number = digits(&chars, index, |x| x.is_ascii_digit());
 
-    while let Some(char) = chars.get(*index){
-        if number_match.contains(char){
-          
-            number += char;
-                
-            *column += 1;
-            *index += 1;
+            //we have to check whether the next thing after a dot is a number, because in theory a
+            //method could come there and then the dot is not part of the number
+            if peek(*index) == Some('.') && peek(*index + 1).is_some_and(|x| x.is_ascii_digit()) {
+                *index += 1;
+                number += ".";
+                number += &digits(&chars, index, |x| x.is_ascii_digit());
+            }
 
-        } else if char == "."{
-            if let Some(next_char) = chars.get(*index + 1){
-                   
+            if let Some(e @ ('e' | 'E')) = peek(*index) {
+                *index += 1;
+                number.push(e);
 
-                //we have to check whether the next thing after a dot is a number, because in
-                //theory a method could come there and then we dont have to throw an error
-                if number_match.contains(next_char){ 
-                    if dot_used {
-                        return Err("Dot can only be used once in numbers!".to_string())
-                    }
-                    
-                    number += char;
+                if let Some(sign @ ('+' | '-')) = peek(*index) {
+                    *index += 1;
+                    number.push(sign);
+                }
 
-                    dot_used = true;
+                let exponent = digits(&chars, index, |x| x.is_ascii_digit());
+                if exponent.is_empty() {
+                    Err("expected digits after the exponent".to_string())
                 } else {
-                      
-                    //reduce the index by one so the dot is not consumed when the next char is not
-                    //a number
-                    *index -= 1;
-                    *column -= 1;
-                    break;
+                    Ok(number + &exponent)
                 }
-
-                    
-                *column += 1;
-                *index += 1;
+            } else if peek(*index) == Some('.') && peek(*index + 1).is_some_and(|x| x.is_ascii_digit()) {
+                Err("a number can only contain one `.`".to_string())
+            } else {
+                Ok(number)
             }
-        } else {
-            
-            //reduce the index by one so the last character is not consumed
-            *index -= 1;
-            *column -= 1;
-            break;
+        }
+    };
+
+    //letters right after a number are not a name but a typo inside of the number
+    let result = match result {
+        Ok(_) if peek(*index).is_some_and(|x| x.is_alphabetic()) => {
+            Err(format!("`{}` is not a valid suffix for a number", peek(*index).unwrap()))
+        },
+        result => result
+    };
+
+    if result.is_err() {
+        //the rest of the number is consumed as well, so 1.2.3 is one error instead of an error
+        //followed by a stray .3
+        while let Some(char) = peek(*index){
+            if !(char.is_alphanumeric() || char == '_' || char == '.') {
+                break;
+            }
+            *index += 1;
         }
     }
 
-    //remove all "_" because they are only for readability
-    let new_number = number.chars().filter(|x| *x != '_').collect::<String>();
+    *column += *index - start_index;
 
-    TokenType::NUM(new_number)
-        .token(start_position.0, start_position.1)
-        .append_to(tokens);
-        
+    //reduce the index by one so the last character is not consumed
+    *index -= 1;
+    *column -= 1;
 
-    Ok(())
+    match result {
+        Ok(number) => {
+            TokenType::NUM(number)
+                .token(start_position.0, start_position.1)
+                .append_to(tokens);
+            Ok(())
+        },
+        Err(reason) => Err(LexErrorKind::MalformedNumber(reason))
+    }
 }
 
-fn string(chars : Vec<String>, index : &mut usize, line : &mut usize, column : &mut usize, tokens : &mut Vec<Token>) -> Result<(), String>{
+//reads the text of a string up to the closing " or the next ${, continued is true when we start after
+//the } of an interpolation, interpolations keeps the brace depth and the start of every interpolation
+//that is still open
+fn string(chars : Vec<String>, index : &mut usize, line : &mut usize, column : &mut usize, tokens : &mut Vec<Token>, continued : bool, interpolations : &mut Vec<(usize, usize)>) -> Result<(), LexErrorKind>{
     
     let mut string = "".to_string();
+
+    //the index of the opening " (or the } we continue after)
+    let string_start = *index - 1;
    
-    let start_position = (line.clone(), column.clone());
+    let start_position = (*line, *column);
+
+    //a bad escape does not end the string, we only report it once the string is done
+    let mut error = None;
+
+    let mut interpolation = false;
 
     while let Some(char) = chars.get(*index){
 
@@ -197,6 +443,27 @@ fn string(chars : Vec<String>, index : &mut usize, line : &mut usize, column : &
             break;
         }
 
+        if char == "$" && chars.get(*index + 1).map(|x| x.as_str()) == Some("{"){
+            //leave the index on the { so it is consumed by the lexer
+            *index += 1;
+            *column += 1;
+            interpolation = true;
+            break;
+        }
+
+        if char == "\\" {
+            match escape(&chars, index) {
+                Ok(escaped) => string.push(escaped),
+                Err(kind) => {
+                    error.get_or_insert(kind);
+                }
+            }
+
+            *column += 1;
+            *index += 1;
+            continue;
+        }
+
         string += char;
 
         if char == "\n"{
@@ -207,22 +474,171 @@ fn string(chars : Vec<String>, index : &mut usize, line : &mut usize, column : &
         *index += 1;
     }
 
+    //we ran out of characters before finding the closing "
+    if *index >= chars.len() {
+        return Err(LexErrorKind::UnterminatedString)
+    }
+
+    if interpolation {
+        interpolations.push((0, string_start));
+    }
+
     //append the string token to the
-    TokenType::STR(string)
+    match (continued, interpolation) {
+        (false, false) => TokenType::STR(string),
+        (false, true) => TokenType::STRHEAD(string),
+        (true, true) => TokenType::STRMID(string),
+        (true, false) => TokenType::STRTAIL(string),
+    }
         .token(start_position.0, start_position.1)
         .append_to(tokens);
 
+    match error {
+        Some(kind) => Err(kind),
+        None => Ok(())
+    }
+}
+
+//turns the escape sequence starting at the \ into the character it stands for, the index is left on
+//the last character of the sequence
+fn escape(chars : &[String], index : &mut usize) -> Result<char, LexErrorKind>{
+    *index += 1;
+
+    let escaped = match chars.get(*index).map(|x| x.as_str()) {
+        Some("n") => '\n',
+        Some("t") => '\t',
+        Some("r") => '\r',
+        Some("0") => '\0',
+        Some("\\") => '\\',
+        Some("\"") => '"',
+        //so ${ can be written without starting an interpolation
+        Some("$") => '$',
+        Some("u") => {
+            if chars.get(*index + 1).map(|x| x.as_str()) != Some("{") {
+                return Err(LexErrorKind::InvalidEscape("expected `{` after `\\u`".to_string()))
+            }
+            *index += 1;
+
+            let mut digits = "".to_string();
+            while let Some(char) = chars.get(*index + 1){
+                if char == "}" || char == "\"" || digits.len() >= 6 {
+                    break;
+                }
+                digits += char;
+                *index += 1;
+            }
+
+            match chars.get(*index + 1).map(|x| x.as_str()) {
+                Some("}") => *index += 1,
+                //there is a 7th digit
+                Some(char) if char != "\"" && digits.len() >= 6 => {
+                    return Err(LexErrorKind::InvalidEscape("unicode escapes have at most 6 digits".to_string()))
+                },
+                _ => return Err(LexErrorKind::InvalidEscape("unicode escapes have to be closed with `}`".to_string()))
+            }
+
+            match u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32) {
+                Some(char) if !digits.is_empty() => char,
+                _ => return Err(LexErrorKind::InvalidEscape(format!("`{}` is not a unicode character", digits)))
+            }
+        },
+        Some(other) => return Err(LexErrorKind::InvalidEscape(format!("`\\{}` is not an escape sequence", other))),
+        //the string is not closed, which is reported by string
+        None => {
+            *index -= 1;
+            return Err(LexErrorKind::UnterminatedString)
+        }
+    };
+
+    Ok(escaped)
+}
+
+//skips a // comment, a /// comment becomes a doc token instead (but //// is just a comment again)
+fn line_comment(chars : Vec<String>, index : &mut usize, line : &mut usize, column : &mut usize, tokens : &mut Vec<Token>) -> Result<(), LexErrorKind>{
+
+    let start_position = (*line, *column);
+
+    let is_doc = chars.get(*index + 2).map(|x| x.as_str()) == Some("/") 
+        && chars.get(*index + 3).map(|x| x.as_str()) != Some("/");
+
+    //skip the slashes
+    let slashes = if is_doc { 3 } else { 2 };
+    *index += slashes;
+    *column += slashes;
+
+    let mut text = "".to_string();
+
+    while let Some(char) = chars.get(*index){
+
+        //the newline is not part of the comment, so the line still gets counted
+        if char == "\n"{
+            break;
+        }
+
+        text += char;
+
+        *index += 1;
+        *column += 1;
+    }
+
+    //reduce the index by one so the last character is not consumed
+    *index -= 1;
+    *column -= 1;
+
+    if is_doc {
+        TokenType::DOC(text)
+            .token(start_position.0, start_position.1)
+            .append_to(tokens);
+    }
 
     Ok(())
 }
 
-fn identifier(chars : Vec<String>, index : &mut usize, line : &mut usize, column : &mut usize, tokens : &mut Vec<Token>) -> Result<(), String>{
+//skips a /* */ comment, these can be nested so commenting out code that has comments in it works
+fn block_comment(chars : Vec<String>, index : &mut usize, line : &mut usize, column : &mut usize) -> Result<(), LexErrorKind>{
+
+    let mut depth = 0;
+
+    while let Some(char) = chars.get(*index){
+        let next_char = chars.get(*index + 1).map(|x| x.as_str());
+
+        match (char.as_str(), next_char) {
+            ("/", Some("*")) => {
+                depth += 1;
+                *index += 1;
+                *column += 1;
+            },
+            ("*", Some("/")) => {
+                depth -= 1;
+                *index += 1;
+                *column += 1;
+
+                //the index stays on the closing / so it is consumed by the lexer
+                if depth == 0 {
+                    return Ok(())
+                }
+            },
+            ("\n", _) => {
+                *line += 1;
+                *column = 0;
+            },
+            _ => ()
+        }
+
+        *index += 1;
+        *column += 1;
+    }
+
+    Err(LexErrorKind::UnterminatedComment)
+}
+
+fn identifier(chars : Vec<String>, index : &mut usize, line : &mut usize, column : &mut usize, tokens : &mut Vec<Token>) -> Result<(), LexErrorKind>{
 
     let id_match = "abcdefghijklmnopqrstuvwxyz_0123456789";
 
     let mut identifier = "".to_string();
 
-    let start_position = (line.clone(), column.clone());
+    let start_position = (*line, *column);
 
     while let Some(char) = chars.get(*index){
 
@@ -256,20 +672,28 @@ fn identifier(chars : Vec<String>, index : &mut usize, line : &mut usize, column
         "and" => TokenType::AND,
         "or" => TokenType::OR,
         "xor" => TokenType::XOR,
+        "is" => TokenType::IS,
     
         "if" => TokenType::IF,
         "else" => TokenType::ELSE,
+        "match" => TokenType::MATCH,
 
         "while" => TokenType::WHILE,
         "for" => TokenType::FOR,
         "break" => TokenType::BREAK,
         "continue" => TokenType::CONTINUE,
         "return" => TokenType::RETURN,
+
+        "in" => TokenType::IN,
+        "to" => TokenType::TO,
+        "step" => TokenType::STEP,
         
         "overload" => TokenType::OVERLOAD,
 
         "type" => TokenType::TYPE,
         "struct" => TokenType::STRUCT,
+        "enum" => TokenType::ENUM,
+        "impl" => TokenType::IMPLBLOCK,
         
 
 
@@ -283,12 +707,34 @@ fn identifier(chars : Vec<String>, index : &mut usize, line : &mut usize, column
     Ok(())
 } 
 
-pub fn lex(text : &'static str) -> Vec<Token>{
+//lexing goes on after an error, so all of them can be reported at once
+pub fn lex(text : &str) -> Result<Vec<Token>, Vec<LexError>>{
 
     let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+
+    //see string
+    let mut interpolations = Vec::new();
 
     let characters : Vec<String> = text.chars().map(|x| x.to_string()).collect();
 
+    //the lexer works on characters, so we precompute where every character sits in the source, this
+    //lets us give every token its exact span once we know which characters it was made from
+    let mut positions = Vec::new();
+    let mut current = Position::default();
+    for char in text.chars(){
+        positions.push(current);
+
+        current.offset += char.len_utf8() as u32;
+        if char == '\n' {
+            current.line += 1;
+            current.column = 0;
+        } else {
+            current.column += 1;
+        }
+    }
+    positions.push(current);
+
     let mut index = 0;
     let mut line = 0;
     let mut column = 0;
@@ -298,7 +744,11 @@ pub fn lex(text : &'static str) -> Vec<Token>{
 
 
     while let Some(char) = characters.get(index){
-        
+
+        let start_index = index;
+        let token_count = tokens.len();
+        let mut result = Ok(());
+
         let mut next_char : Option<&str> = None;
 
         if let Some(next) = characters.get(index + 1){
@@ -310,32 +760,99 @@ pub fn lex(text : &'static str) -> Vec<Token>{
             ")" => TokenType::RPAREN.token(line, column).append_to(&mut tokens),
             "[" => TokenType::LBRACK.token(line, column).append_to(&mut tokens),
             "]" => TokenType::RBRACK.token(line, column).append_to(&mut tokens),
-            "{" => TokenType::LBRACE.token(line, column).append_to(&mut tokens),
-            "}" => TokenType::RBRACE.token(line, column).append_to(&mut tokens),
+            "{" => {
+                if let Some((depth, _)) = interpolations.last_mut(){
+                    *depth += 1;
+                }
+                TokenType::LBRACE.token(line, column).append_to(&mut tokens)
+            },
+            "}" => {
+                match interpolations.last_mut() {
+                    //this closes the interpolation, so the string goes on after it
+                    Some((0, _)) => {
+                        interpolations.pop();
+                        index += 1;
+                        result = string(characters.clone(), &mut index, &mut line, &mut column, &mut tokens, true, &mut interpolations);
+                    },
+                    Some((depth, _)) => {
+                        *depth -= 1;
+                        TokenType::RBRACE.token(line, column).append_to(&mut tokens)
+                    },
+                    None => TokenType::RBRACE.token(line, column).append_to(&mut tokens)
+                }
+            },
             
 
-            "+" => TokenType::PLUS.token(line, column).append_to(&mut tokens),
+            "+" => {
+                match next_char{
+                    Some("=") => {
+                        index += 1;
+                        TokenType::PLUSEQ.token(line, column).append_to(&mut tokens)
+                    },
+                    _ => TokenType::PLUS.token(line, column).append_to(&mut tokens),
+                }
+            },
             "-" => {
                 match next_char{
                     Some(">") => {
                         index += 1;
                         TokenType::ARROW.token(line, column).append_to(&mut tokens)
                     },
+                    Some("=") => {
+                        index += 1;
+                        TokenType::MINUSEQ.token(line, column).append_to(&mut tokens)
+                    },
                     _ => TokenType::MINUS.token(line, column).append_to(&mut tokens),
                 }
             },
-            "*" => TokenType::STAR.token(line, column).append_to(&mut tokens),
-            "/" => TokenType::SLASH.token(line, column).append_to(&mut tokens),
+            "*" => {
+                match next_char{
+                    Some("*") => {
+                        index += 1;
+                        TokenType::STARSTAR.token(line, column).append_to(&mut tokens)
+                    },
+                    Some("=") => {
+                        index += 1;
+                        TokenType::STAREQ.token(line, column).append_to(&mut tokens)
+                    },
+                    _ => TokenType::STAR.token(line, column).append_to(&mut tokens),
+                }
+            },
+            "/" => {
+                match next_char{
+                    Some("/") => result = line_comment(characters.clone(), &mut index, &mut line, &mut column, &mut tokens),
+                    Some("*") => result = block_comment(characters.clone(), &mut index, &mut line, &mut column),
+                    Some("=") => {
+                        index += 1;
+                        TokenType::SLASHEQ.token(line, column).append_to(&mut tokens)
+                    },
+                    _ => TokenType::SLASH.token(line, column).append_to(&mut tokens),
+                }
+            },
+            "%" => TokenType::PERCENT.token(line, column).append_to(&mut tokens),
             
             "|" => TokenType::BAR.token(line, column).append_to(&mut tokens),
+            "&" => TokenType::AMP.token(line, column).append_to(&mut tokens),
+            "^" => TokenType::CARET.token(line, column).append_to(&mut tokens),
 
             "," => TokenType::COMMA.token(line, column).append_to(&mut tokens),
-            ":" => TokenType::COLON.token(line, column).append_to(&mut tokens),
+            ":" => {
+                match next_char{
+                    Some(":") => {
+                        index += 1;
+                        TokenType::COLONCOLON.token(line, column).append_to(&mut tokens)
+                    },
+                    _ => TokenType::COLON.token(line, column).append_to(&mut tokens),
+                }
+            },
             "." => {
                 match next_char {
                     Some(char) => { 
                         if num_start_match.contains(char){
-                            let _ = number(characters.clone(), &mut index, &mut line, &mut column, &mut tokens); 
+                            result = number(characters.clone(), &mut index, &mut line, &mut column, &mut tokens); 
+                        } else if char == "." && characters.get(index + 2).map(|x| x.as_str()) == Some(".") {
+                            index += 2;
+                            TokenType::DOTDOTDOT.token(line, column).append_to(&mut tokens)
                         } else {
                             TokenType::DOT.token(line, column).append_to(&mut tokens)
                         }
@@ -375,6 +892,10 @@ pub fn lex(text : &'static str) -> Vec<Token>{
                         index += 1;
                         TokenType::LEQ.token(line, column).append_to(&mut tokens)
                     },
+                    Some("<") => {
+                        index += 1;
+                        TokenType::SHL.token(line, column).append_to(&mut tokens)
+                    },
                     _ => TokenType::LE.token(line, column).append_to(&mut tokens),
                 }
             },
@@ -385,37 +906,156 @@ pub fn lex(text : &'static str) -> Vec<Token>{
                         index += 1;
                         TokenType::GEQ.token(line, column).append_to(&mut tokens)
                     },
+                    Some(">") => {
+                        index += 1;
+                        TokenType::SHR.token(line, column).append_to(&mut tokens)
+                    },
                     _ => TokenType::GE.token(line, column).append_to(&mut tokens),
                 }
             }
 
             "\n" => { line += 1 } 
 
+            _ if char.trim().is_empty() => (),
 
-            _ => ()
+            //these are handled below
+            _ if id_start_match.contains(char) 
+                || id_start_match.to_uppercase().contains(char) 
+                || num_start_match.contains(char)
+                || char == "\"" => (),
+
+            _ => result = Err(LexErrorKind::UnexpectedCharacter(char.chars().next().unwrap_or_default()))
         }
 
 
         if id_start_match.contains(char) || id_start_match.to_uppercase().contains(char){
-            let _ = identifier(characters.clone(), &mut index, &mut line, &mut column, &mut tokens);
+            result = identifier(characters.clone(), &mut index, &mut line, &mut column, &mut tokens);
         }
 
         if num_start_match.contains(char) {
-            let _ = number(characters.clone(), &mut index, &mut line, &mut column, &mut tokens);
+            result = number(characters.clone(), &mut index, &mut line, &mut column, &mut tokens);
         }
 
         if char == "\""{
             //we need to consume the " symbol so we dont immediately end the string
             index += 1;
-            let _ = string(characters.clone(), &mut index, &mut line, &mut column, &mut tokens);
+            result = string(characters.clone(), &mut index, &mut line, &mut column, &mut tokens, false, &mut interpolations);
         }
         
+        //every pass adds at most one token which spans from where we started to the last consumed
+        //character
+        let end = (index + 1).min(positions.len() - 1);
+        if let Some(token) = tokens.get_mut(token_count){
+            token.span = Span::new(positions[start_index], positions[end]);
+        }
+
+        if let Err(kind) = result{
+            errors.push(LexError{
+                kind,
+                span : Span::new(positions[start_index], positions[end])
+            });
+        }
+
         column += 1;
         index += 1;
 
     }
 
+    let end = positions[positions.len() - 1];
+
+    //strings whose interpolation was never closed
+    for (_, start) in interpolations{
+        errors.push(LexError{
+            kind : LexErrorKind::UnterminatedString,
+            span : Span::new(positions[start], end)
+        });
+    }
+
     TokenType::EOF.token(line, column).append_to(&mut tokens);
+    if let Some(eof) = tokens.last_mut(){
+        eof.span = Span::new(end, end);
+    }
+
+    if errors.is_empty() {
+        Ok(tokens)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::*;
+
+    fn errors(text : &'static str) -> Vec<LexErrorKind>{
+        lex(text).unwrap_err().into_iter().map(|error| error.kind).collect()
+    }
+
+    #[test]
+    fn unterminated_strings(){
+        let errors = lex("let s = \"abc;").unwrap_err();
+
+        assert_eq!(errors[0].kind, LexErrorKind::UnterminatedString);
+        assert_eq!((errors[0].span.start.offset, errors[0].span.end.offset), (8, 13));
+    }
+
+    #[test]
+    fn malformed_numbers(){
+        assert_eq!(errors("let a = 1.2.3;"), vec![LexErrorKind::MalformedNumber("a number can only contain one `.`".to_string())]);
+    }
+
+    #[test]
+    fn every_unexpected_character_is_reported(){
+        assert_eq!(errors("let a = 1 # 2 @;"), vec![LexErrorKind::UnexpectedCharacter('#'), LexErrorKind::UnexpectedCharacter('@')]);
+    }
+
+    fn types(text : &'static str) -> Vec<TokenType>{
+        lex(text).unwrap().into_iter().map(|token| token.r#type).collect()
+    }
+
+    fn id(name : &str) -> TokenType{
+        TokenType::ID(name.to_string())
+    }
 
-    tokens
+    #[test]
+    fn comments_are_skipped(){
+        assert_eq!(types("a // c\nb"), vec![id("a"), id("b"), TokenType::EOF]);
+        assert_eq!(types("a /* x /* nested */ y */ b"), vec![id("a"), id("b"), TokenType::EOF]);
+        assert_eq!(types("//// not a doc comment\na"), vec![id("a"), TokenType::EOF]);
+    }
+
+    #[test]
+    fn block_comments_keep_counting_lines(){
+        let tokens = lex("/* one\ntwo\n*/ a").unwrap();
+
+        assert_eq!((tokens[0].span.start.line, tokens[0].span.start.column), (2, 3));
+    }
+
+    #[test]
+    fn doc_comments_are_tokens(){
+        assert_eq!(types("/// adds\nfn"), vec![TokenType::DOC(" adds".to_string()), TokenType::FN, TokenType::EOF]);
+    }
+
+    #[test]
+    fn unterminated_block_comments(){
+        assert_eq!(errors("a /* b /* c */"), vec![LexErrorKind::UnterminatedComment]);
+    }
+
+    #[test]
+    fn escape_sequences(){
+        assert_eq!(types(r#""a\n\t\"\\\$\u{1F600}""#), vec![TokenType::STR("a\n\t\"\\$\u{1F600}".to_string()), TokenType::EOF]);
+    }
+
+    #[test]
+    fn invalid_escape_sequences(){
+        assert_eq!(errors(r#""\q""#), vec![LexErrorKind::InvalidEscape("`\\q` is not an escape sequence".to_string())]);
+        assert_eq!(errors(r#""\u{110000}""#), vec![LexErrorKind::InvalidEscape("`110000` is not a unicode character".to_string())]);
+    }
+
+    #[test]
+    fn unicode_escapes_have_at_most_six_digits(){
+        assert_eq!(types(r#""\u{10FFFF}""#), vec![TokenType::STR("\u{10FFFF}".to_string()), TokenType::EOF]);
+        assert_eq!(errors(r#""\u{0000041}""#), vec![LexErrorKind::InvalidEscape("unicode escapes have at most 6 digits".to_string())]);
+        assert_eq!(errors(r#""\u{41""#), vec![LexErrorKind::InvalidEscape("unicode escapes have to be closed with `}`".to_string())]);
+    }
 }
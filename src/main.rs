@@ -2,35 +2,127 @@ mod lexer;
 mod parser;
 mod errors;
 mod types;
+mod interpreter;
+mod diagnostics;
+mod source;
+mod repl;
 
 pub use lexer::*;
 pub use parser::*;
 pub use errors::*;
 pub use types::*;
+pub use interpreter::*;
+pub use diagnostics::*;
+pub use source::*;
+pub use repl::*;
 
-fn main() {
+use std::io::{IsTerminal, Write};
+use std::process::ExitCode;
 
+const USAGE : &str = "usage: bifrost <command> <file>
 
-    let text = r#"
-   
+commands:
+    run <file>       type checks and runs the program
+    check <file>     only parses and type checks the program
+    tokens <file>    prints the tokens of the file
+    ast <file>       prints the syntax tree of the file
+    repl             starts an interactive session
+    help             prints this message";
 
-            struct vec3 {
-                x : num,
-                y : num,
-                z : num
-            }            
-    
+//the program had errors in it
+const FAILURE : u8 = 1;
+//bifrost was used wrong or the file could not be read
+const USAGE_ERROR : u8 = 2;
 
-            
-        "#;
-    
-    let tokens = lex(text);
+//the interpreter and the checker recurse along the program, so they run on a thread with a stack that
+//fits MAX_CALL_DEPTH calls even in debug builds. the memory is only reserved, not used up front
+const STACK_SIZE : usize = 512 * 1024 * 1024;
 
-    //println!("{:#?}", tokens.clone());
+fn main() -> ExitCode {
+    std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(start)
+        .and_then(|thread| thread.join().map_err(|_| std::io::Error::other("bifrost crashed")))
+        .unwrap_or_else(|error| {
+            eprintln!("error: {}", error);
+            ExitCode::from(FAILURE)
+        })
+}
+
+fn start() -> ExitCode {
+    let arguments : Vec<String> = std::env::args().skip(1).collect();
+
+    let (command, path) = match arguments.as_slice() {
+        [command] if command == "help" || command == "--help" || command == "-h" => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS
+        },
+        [command] if command == "repl" => {
+            repl();
+            return ExitCode::SUCCESS
+        },
+        [command, path] => (command.as_str(), path),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(USAGE_ERROR)
+        }
+    };
+
+    if !["run", "check", "tokens", "ast"].contains(&command) {
+        eprintln!("error: unknown command `{}`\n\n{}", command, USAGE);
+        return ExitCode::from(USAGE_ERROR)
+    }
+
+    let mut sources = SourceMap::new();
+    let file = match sources.load(path) {
+        Ok(file) => file,
+        Err(error) => {
+            eprintln!("error: could not read `{}`: {}", path, error);
+            return ExitCode::from(USAGE_ERROR)
+        }
+    };
+
+    let result = execute(command, sources.get(file).unwrap());
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(errors) => {
+            let colored = std::io::stderr().is_terminal() && std::env::var("NO_COLOR").is_err();
+            for error in errors{
+                eprint!("{}", error.diagnostic().render_in(&sources, colored));
+            }
+            ExitCode::from(FAILURE)
+        }
+    }
+}
+
+fn execute(command : &str, file : &SourceFile) -> Result<(), Vec<Error>>{
+    let tokens = file.lex().map_err(|errors| errors.into_iter().map(Error::from).collect::<Vec<Error>>())?;
+
+    //the dumps are often piped into other tools that might stop reading early, which is not an error
+    let mut out = std::io::stdout().lock();
+
+    if command == "tokens" {
+        for token in tokens{
+            let _ = writeln!(out, "{}:{}\t{:?}", token.span.start.line + 1, token.span.start.column + 1, token.r#type);
+        }
+        return Ok(())
+    }
+
+    let ast = parse(tokens)?;
+
+    if command == "ast" {
+        let _ = writeln!(out, "{:#?}", ast);
+        return Ok(())
+    }
+
+    drop(out);
 
-    let ast = parse(tokens);
+    type_check(ast.clone()).map_err(|error| vec![error])?;
 
-    
+    if command == "run" {
+        interpret(ast).map_err(|error| vec![error])?;
+    }
 
-    println!("{:#?}", ast);
+    Ok(())
 }
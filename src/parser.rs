@@ -1,5 +1,3 @@
-use std::env::current_dir;
-
 use crate::*;
 
 
@@ -14,6 +12,13 @@ pub enum Type{
     BoolType,
 
     CustomType(String),
+    //the T inside of fn first<T>(xs : T[]) -> T, struct Pair<A, B> { ... } or type Maybe<T> = T | nil
+    Param(String),
+    //a generic struct or type with its parameters filled in: Pair<num, string>
+    Applied{
+        name : String,
+        arguments : Vec<Type>
+    },
    
     ArrayType(Box<Type>),
     UnionType(Vec<Type>),
@@ -28,13 +33,57 @@ pub enum Type{
         keys : Vec<String>,
         types : Vec<Type>
     },
+    //enums are named just like structs, every variant can carry a payload
+    Enum{
+        variants : Vec<String>,
+        payloads : Vec<Payload>
+    },
 
     FunctionType{
         arguments : Vec<Type>,
         returns : Box<Type>
+    },
+
+    //a type the type checker has not worked out yet, these only exist while checking
+    Inferred(usize)
+}
+
+//what a variant of an enum carries: Empty, Circle(num) or Rect { w : num, h : num }
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Payload{
+    Unit,
+    Tuple(Vec<Type>),
+    Fields{
+        keys : Vec<String>,
+        types : Vec<Type>
     }
+}
 
+impl Payload{
+    //how a variant with this payload is built, path is the Name::Variant in front of it
+    pub fn usage(&self, path : &str) -> String{
+        match self {
+            Payload::Unit => path.to_string(),
+            Payload::Tuple(types) => format!("{}({})", path, vec!["..."; types.len()].join(", ")),
+            Payload::Fields { keys, .. } => {
+                let fields = keys.iter().map(|key| format!("{} : ...", key)).collect::<Vec<String>>();
+                format!("{} {{ {} }}", path, fields.join(", "))
+            }
+        }
+    }
+}
 
+impl std::fmt::Display for Payload{
+    fn fmt(&self, f : &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Payload::Unit => Ok(()),
+            Payload::Tuple(types) => {
+                let types = types.iter().map(|x| x.to_string()).collect::<Vec<String>>();
+                write!(f, "({})", types.join(", "))
+            },
+            Payload::Fields { keys, types } => write!(f, " {}", Type::ObjectType{ keys : keys.clone(), types : types.clone() })
+        }
+    }
 }
 
 impl Type{
@@ -54,12 +103,120 @@ impl Type{
             }
         }      
     }
+
+    //rebuilds the type with every part that replacement gives something for swapped out
+    pub fn replace(&self, replacement : &dyn Fn(&Type) -> Option<Type>) -> Type{
+        if let Some(replaced) = replacement(self){
+            return replaced
+        }
+
+        let all = |types : &Vec<Type>| types.iter().map(|x| x.replace(replacement)).collect::<Vec<Type>>();
+
+        match self {
+            Type::Applied { name, arguments } => Type::Applied{
+                name : name.to_string(),
+                arguments : all(arguments)
+            },
+            Type::ArrayType(inner) => Type::ArrayType(Box::new(inner.replace(replacement))),
+            Type::UnionType(options) => Type::UnionType(all(options)),
+            Type::ObjectType { keys, types } => Type::ObjectType{
+                keys : keys.clone(),
+                types : all(types)
+            },
+            Type::Struct { keys, types } => Type::Struct{
+                keys : keys.clone(),
+                types : all(types)
+            },
+            Type::Enum { variants, payloads } => Type::Enum{
+                variants : variants.clone(),
+                payloads : payloads.iter().map(|payload| match payload {
+                    Payload::Unit => Payload::Unit,
+                    Payload::Tuple(types) => Payload::Tuple(all(types)),
+                    Payload::Fields { keys, types } => Payload::Fields{
+                        keys : keys.clone(),
+                        types : all(types)
+                    }
+                }).collect()
+            },
+            Type::FunctionType { arguments, returns } => Type::FunctionType{
+                arguments : all(arguments),
+                returns : Box::new(returns.replace(replacement))
+            },
+            other => other.clone()
+        }
+    }
+
+    //the names of the generic parameters are parsed like any other type name, this turns them into parameters
+    pub fn parameterize(&self, generics : &[String]) -> Type{
+        self.replace(&|x| match x {
+            Type::CustomType(name) if generics.contains(name) => Some(Type::Param(name.to_string())),
+            _ => None
+        })
+    }
+}
+
+//this is how types are written in bifrost code, so errors show them the way the user wrote them
+impl std::fmt::Display for Type{
+    fn fmt(&self, f : &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Type::NullType => write!(f, "nil"),
+            Type::AnyType => write!(f, "any"),
+            Type::NumType => write!(f, "num"),
+            Type::StrType => write!(f, "string"),
+            Type::BoolType => write!(f, "bool"),
+            Type::CustomType(name) | Type::Param(name) => write!(f, "{}", name),
+            Type::Applied { name, arguments } => {
+                let arguments = arguments.iter().map(|x| x.to_string()).collect::<Vec<String>>();
+                write!(f, "{}<{}>", name, arguments.join(", "))
+            },
+            Type::ArrayType(inner) => {
+                match **inner {
+                    Type::UnionType(_) | Type::FunctionType { .. } => write!(f, "({})[]", inner),
+                    _ => write!(f, "{}[]", inner)
+                }
+            },
+            Type::UnionType(options) => {
+                let options = options.iter().map(|x| x.to_string()).collect::<Vec<String>>();
+                write!(f, "{}", options.join(" | "))
+            },
+            Type::ObjectType { keys, types } | Type::Struct { keys, types } => {
+                let fields = keys.iter().zip(types.iter())
+                    .map(|(key, field_type)| format!("{} : {}", key, field_type))
+                    .collect::<Vec<String>>();
+
+                if fields.is_empty() {
+                    return write!(f, "{{}}")
+                }
+                write!(f, "{{ {} }}", fields.join(", "))
+            },
+            Type::Enum { variants, payloads } => {
+                let variants = variants.iter().zip(payloads.iter())
+                    .map(|(variant, payload)| format!("{}{}", variant, payload))
+                    .collect::<Vec<String>>();
+                write!(f, "enum {{ {} }}", variants.join(", "))
+            },
+            Type::FunctionType { arguments, returns } => {
+                let arguments = arguments.iter().map(|x| x.to_string()).collect::<Vec<String>>();
+                write!(f, "fn({}) -> {}", arguments.join(", "), returns)
+            },
+            Type::Inferred(_) => write!(f, "_")
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TypedName{
-    name : Token,
-    r#type : Type
+    pub name : Token,
+    pub r#type : Type,
+    //names without a type annotation have any as their type, the type checker tries to infer a better one
+    pub annotated : bool
+}
+
+impl TypedName{
+    //the first argument of a method is called self
+    pub fn is_self(&self) -> bool{
+        self.name.r#type.get_id_val().as_deref() == Some("self")
+    }
 }
 
 type FallibleType = Result<Type, Error>;
@@ -67,8 +224,9 @@ type FallibleType = Result<Type, Error>;
 //this is for typings in let and so on
 fn typed_primary(tokens : &Vec<Token>, current_index : &mut usize) -> Result<TypedName, Error>{
     let name = get_current_token(tokens, current_index)?;
-    
-    consume_token(tokens, current_index)?;
+
+    //only names can be bound, for 5 in ... is not a loop
+    match_token(tokens, current_index, TokenType::ID_)?;
 
     if match_tokens(tokens, current_index, vec![
         TokenType::COLON
@@ -78,12 +236,14 @@ fn typed_primary(tokens : &Vec<Token>, current_index : &mut usize) -> Result<Typ
 
         Ok(TypedName{
             name,
-            r#type : constructed
+            r#type : constructed,
+            annotated : true
         })   
     } else {
         Ok(TypedName{
             name,
-            r#type : Type::AnyType
+            r#type : Type::AnyType,
+            annotated : false
         })
     }
 
@@ -159,12 +319,29 @@ fn typed(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleType{
                     "any" => {
                         left = Type::AnyType;
                     }
+                    _ if match_tokens(tokens, current_index, vec![TokenType::LE])? => {
+                        left = Type::Applied{
+                            name : id_type.to_string(),
+                            arguments : type_arguments(tokens, current_index)?
+                        }
+                    },
                     _ => {
                         left = Type::CustomType(id_type.to_string())
                     }
                 }
 
             },
+            //nil is a keyword so it never reaches the ID branch
+            TokenType::NIL => {
+                if changed{
+                    return Ok(left)
+                } else {
+                    changed = true
+                }
+
+                consume_token(tokens, current_index)?;
+                left = Type::NullType;
+            },
             TokenType::FN => {
 
                 if changed{
@@ -175,8 +352,6 @@ fn typed(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleType{
                 left = function_typed(tokens, current_index)?;
             }
             _ => {
-
-                println!("{:?}", left);
                 return Ok(left)
             }
         }
@@ -187,6 +362,61 @@ fn typed(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleType{
     Ok(left)
 }
 
+//the <num, string> of Pair<num, string>
+fn type_arguments(tokens : &Vec<Token>, current_index : &mut usize) -> Result<Vec<Type>, Error>{
+    let open = *current_index;
+    match_token(tokens, current_index, TokenType::LE)?;
+
+    let mut arguments = vec![typed(tokens, current_index)?];
+    while match_tokens(tokens, current_index, vec![TokenType::COMMA])? {
+        consume_token(tokens, current_index)?;
+        arguments.push(typed(tokens, current_index)?);
+    }
+
+    //the lexer reads the >> of Pair<Maybe<num>> as a shift, its first half closes the innermost list
+    //and is left there for the list around it, which then still has one < more than it has >
+    if match_tokens(tokens, current_index, vec![TokenType::SHR])? {
+        let (opened, closed) = tokens[open..*current_index].iter().fold((0, 0), |(opened, closed), token| match token.r#type {
+            TokenType::LE => (opened + 1, closed),
+            TokenType::GE => (opened, closed + 1),
+            TokenType::SHR => (opened, closed + 2),
+            _ => (opened, closed)
+        });
+
+        if opened - closed > 1 {
+            consume_token(tokens, current_index)?;
+        }
+        return Ok(arguments)
+    }
+
+    match_token(tokens, current_index, TokenType::GE)?;
+
+    Ok(arguments)
+}
+
+//the <T, U> after the name of a generic function, struct or type
+fn generics(tokens : &Vec<Token>, current_index : &mut usize) -> Result<Vec<String>, Error>{
+    let mut generics = Vec::new();
+    if !match_tokens(tokens, current_index, vec![TokenType::LE])? {
+        return Ok(generics)
+    }
+    consume_token(tokens, current_index)?;
+
+    loop {
+        let name = get_current_token(tokens, current_index)?;
+        match_token(tokens, current_index, TokenType::ID_)?;
+        generics.push(name.r#type.get_id_val().unwrap_or_default());
+
+        if !match_tokens(tokens, current_index, vec![TokenType::COMMA])? {
+            break;
+        }
+        consume_token(tokens, current_index)?;
+    }
+    match_token(tokens, current_index, TokenType::GE)?;
+
+    Ok(generics)
+}
+
 fn function_typed(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleType{
     match_token(tokens, current_index, TokenType::FN)?;
 
@@ -196,7 +426,7 @@ fn function_typed(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleT
     
     let mut trailing_comma : Option<Token> = None;
 
-    while let Some(token) = tokens.get(*current_index){
+    while tokens.get(*current_index).is_some(){
 
         if match_tokens(tokens, current_index, vec![
             TokenType::RPAREN
@@ -257,7 +487,16 @@ fn object_typed(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleTyp
     let mut keys = Vec::new();
     let mut types = Vec::new();
 
-    while let Some(token) = tokens.get(*current_index){
+    //{} is the type of the empty object
+    if match_tokens(tokens, current_index, vec![TokenType::RBRACE])? {
+        consume_token(tokens, current_index)?;
+        return Ok(Type::ObjectType{
+            keys,
+            types
+        })
+    }
+
+    while tokens.get(*current_index).is_some(){
         
         let name = get_current_token(tokens, current_index)?; 
         match_token(tokens, current_index, TokenType::ID_)?;
@@ -305,17 +544,159 @@ fn object_typed(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleTyp
 }
 
 
+//a number literal, the value is what we compute with while the text is what was written (without the _)
+//so it can be shown as is. equality and hashing go by the value, 0x1F and 31 are the same number, which
+//works since a literal is never NaN
+#[derive(Clone, Debug)]
+pub struct Number{
+    pub value : f64,
+    pub text : String
+}
+
+impl Number{
+    //none if the number is too large to be represented
+    pub fn parse(text : &str) -> Option<Number>{
+        let radix = match text.get(..2) {
+            Some("0x") => 16,
+            Some("0b") => 2,
+            Some("0o") => 8,
+            _ => 10
+        };
+
+        //f64 can only parse decimals, the others are added up digit by digit so they cannot overflow
+        //anything but the f64 itself
+        let value = if radix == 10 {
+            text.parse::<f64>().ok()?
+        } else {
+            let mut value = 0.0;
+            for digit in text[2..].chars(){
+                value = value * radix as f64 + digit.to_digit(radix)? as f64;
+            }
+            value
+        };
+
+        if !value.is_finite() {
+            return None
+        }
+
+        Some(Number{
+            value,
+            text : text.to_string()
+        })
+    }
+}
+
+impl PartialEq for Number{
+    fn eq(&self, other : &Self) -> bool {
+        self.value.to_bits() == other.value.to_bits()
+    }
+}
+
+impl Eq for Number{}
+
+impl std::hash::Hash for Number{
+    fn hash<H : std::hash::Hasher>(&self, state : &mut H) {
+        self.value.to_bits().hash(state)
+    }
+}
+
+impl std::fmt::Display for Number{
+    fn fmt(&self, f : &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+//one of the things between the braces of an object literal
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ObjectEntry{
+    //key : value, the shorthand { x } is the same as { x : x }
+    Field{
+        key : Token,
+        value : Expression
+    },
+    //...value copies all fields of value into the object
+    Spread{
+        operator : Token,
+        value : Expression
+    }
+}
+
+//the left side of a match arm, what a value has to look like for the arm to be taken
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern{
+    pub kind : PatternKind,
+    pub span : Span
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatternKind{
+    //_
+    Wildcard,
+    //1, "a", true or nil, matches values equal to it
+    Literal(Expression),
+    //x, matches anything and binds it to x
+    Binding(String),
+    //x : num or _ : num, matches values of the type
+    Typed{
+        name : Option<String>,
+        r#type : Type
+    },
+    //vec3 { x, y : 0 }, fields that are left out can be anything
+    Struct{
+        name : Token,
+        fields : Vec<(Token, Pattern)>
+    },
+    //{ x, y : 0 }
+    Object(Vec<(Token, Pattern)>),
+    //[first, ...rest], without a rest the length has to be the same
+    Array{
+        elements : Vec<Pattern>,
+        rest : Option<Box<Pattern>>
+    },
+    //Shape::Point, Shape::Circle(r) or Shape::Rect { w, h }
+    Variant{
+        enum_name : Token,
+        variant : Token,
+        payload : PatternPayload
+    }
+}
+
+//the patterns for the payload of a variant, laid out like the Payload itself
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatternPayload{
+    Unit,
+    Tuple(Vec<Pattern>),
+    Fields(Vec<(Token, Pattern)>)
+}
+
+//pattern if guard => body
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchArm{
+    pub pattern : Pattern,
+    pub guard : Option<Expression>,
+    pub body : Expression
+}
+
+//every node of the tree knows which part of the source it was parsed from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expression{
+    pub kind : ExpressionKind,
+    pub span : Span
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub enum Expression {
+pub enum ExpressionKind {
 
     LiteralStr(String),
-    LiteralNum(u64, u64),
+    LiteralNum(Number),
     LiteralBool(bool),
     LiteralID(String),
     LiteralNil,
+    //a string with ${...} in it, the parts are the pieces of text and the interpolated expressions
+    Interpolated(Vec<Expression>),
 
     LiteralArray(Vec<Expression>),
-    LiteralObject(Vec<Expression>, Vec<Expression>),
+    LiteralObject(Vec<ObjectEntry>),
 
     Binary{
         left : Box<Expression>,
@@ -328,6 +709,12 @@ pub enum Expression {
         right : Box<Expression>
     },
 
+    //x is num, checks the type of a value at runtime
+    Is{
+        value : Box<Expression>,
+        r#type : Type
+    },
+
     Grp{
         inner : Box<Expression>
     },
@@ -350,16 +737,27 @@ pub enum Expression {
         block : Box<Expression>
     },
 
+    Match{
+        value : Box<Expression>,
+        arms : Vec<MatchArm>
+    },
+
     For{
-        condition : Box<Expression>,
+        //the name the current element (or number) is bound to, optionally typed
+        binding : TypedName,
+        range : ForRange,
         block : Box<Expression>
     },
 
     Fn{
         name : Option<Token>,
+        //the type parameters of generic functions: fn first<T>(xs : T[]) -> T
+        generics : Vec<String>,
         arguments : Vec<TypedName>,
         result : Option<Type>,
-        body : Box<Expression>
+        body : Box<Expression>,
+        //the /// comments in front of the declaration
+        docs : Vec<Token>
     },
 
     FunctionCall{
@@ -367,9 +765,21 @@ pub enum Expression {
         arguments: Vec<Expression>
     },
 
-    FieldCall{
+    //target.field
+    FieldAccess{
+        target : Box<Expression>,
+        field : Token
+    },
+    //target[index]
+    Index{
         target : Box<Expression>,
-        value : Box<Expression>
+        index : Box<Expression>
+    },
+    //target.method(arguments)
+    MethodCall{
+        target : Box<Expression>,
+        method : Token,
+        arguments : Vec<Expression>
     },
 
     Assign{
@@ -384,17 +794,39 @@ pub enum Expression {
     },
     TypeDeclaration{
         name : Token,
-        r#type : Type 
+        generics : Vec<String>,
+        r#type : Type,
+        docs : Vec<Token>
     },
     StructDeclaration{
         name : Token, 
-        r#type : Type
+        generics : Vec<String>,
+        r#type : Type,
+        docs : Vec<Token>
+    },
+    EnumDeclaration{
+        name : Token,
+        r#type : Type,
+        docs : Vec<Token>
     },
+    //Name { field : value } or, for variants of enums, Name::Variant { field : value }
     StructUsage{
         struct_name : Token,
+        variant : Option<Token>,
         fields : Vec<Token>,
         values : Vec<Expression>
     },
+    //impl Name { fn ... }, functions that take self as their first argument are methods, the others
+    //can only be reached through Name::function
+    Impl{
+        struct_name : Token,
+        functions : Vec<Expression>
+    },
+    //Name::function
+    Associated{
+        struct_name : Token,
+        function : Token
+    },
 
     Overload{
         operation : Token,
@@ -406,14 +838,129 @@ pub enum Expression {
     Return(Box<Expression>),
     Break,
     Continue,
+
+    //placeholder for something that could not be parsed
+    Error(Error),
 }
 
 
+//a for loop either walks over something iterable (for x in xs) or counts numerically from start
+//to end (for i = 0 to 10 step 2)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ForRange{
+    In(Box<Expression>),
+    To{
+        start : Box<Expression>,
+        end : Box<Expression>,
+        step : Option<Box<Expression>>
+    }
+}
+
 impl Expression{
-    pub fn expr(&self) -> Result<Expression, Error>{
-        return Ok(self.clone())
+    //the expressions directly nested inside of this one
+    pub fn children(&self) -> Vec<&Expression>{
+        match &self.kind {
+            ExpressionKind::LiteralStr(_)
+                | ExpressionKind::LiteralNum(_)
+                | ExpressionKind::LiteralBool(_)
+                | ExpressionKind::LiteralID(_)
+                | ExpressionKind::LiteralNil
+                | ExpressionKind::TypeDeclaration { .. }
+                | ExpressionKind::StructDeclaration { .. }
+                | ExpressionKind::EnumDeclaration { .. }
+                | ExpressionKind::Associated { .. }
+                | ExpressionKind::Break
+                | ExpressionKind::Continue
+                | ExpressionKind::Error(_) => vec![],
+
+            ExpressionKind::LiteralArray(elements) | ExpressionKind::Interpolated(elements) => elements.iter().collect(),
+            ExpressionKind::LiteralObject(entries) => entries.iter().map(|entry| match entry {
+                ObjectEntry::Field { value, .. } | ObjectEntry::Spread { value, .. } => value
+            }).collect(),
+            ExpressionKind::Binary { left, right, .. } => vec![left, right],
+            ExpressionKind::Unary { right, .. } => vec![right],
+            ExpressionKind::Is { value, .. } => vec![value],
+            ExpressionKind::Grp { inner } => vec![inner],
+            ExpressionKind::Block { expressions } | ExpressionKind::Impl { functions : expressions, .. } => expressions.iter().collect(),
+            ExpressionKind::If { condition, if_block, else_if_blocks, else_block } => {
+                let mut children : Vec<&Expression> = vec![condition, if_block];
+                for (condition, block) in else_if_blocks{
+                    children.push(condition);
+                    children.push(block);
+                }
+                if let Some(else_block) = else_block{
+                    children.push(else_block);
+                }
+                children
+            },
+            ExpressionKind::While { condition, block } => vec![condition, block],
+            ExpressionKind::Match { value, arms } => {
+                let mut children : Vec<&Expression> = vec![value];
+                for arm in arms{
+                    if let Some(guard) = &arm.guard{
+                        children.push(guard);
+                    }
+                    children.push(&arm.body);
+                }
+                children
+            },
+            ExpressionKind::For { range, block, .. } => {
+                let mut children : Vec<&Expression> = match range {
+                    ForRange::In(iterable) => vec![iterable],
+                    ForRange::To { start, end, step } => {
+                        let mut bounds : Vec<&Expression> = vec![start, end];
+                        if let Some(step) = step{
+                            bounds.push(step);
+                        }
+                        bounds
+                    }
+                };
+                children.push(block);
+                children
+            },
+            ExpressionKind::Fn { body, .. } | ExpressionKind::Overload { body, .. } => vec![body],
+            ExpressionKind::FunctionCall { function, arguments } => {
+                let mut children : Vec<&Expression> = vec![function];
+                children.extend(arguments.iter());
+                children
+            },
+            ExpressionKind::FieldAccess { target, .. } => vec![target],
+            ExpressionKind::Index { target, index } => vec![target, index],
+            ExpressionKind::MethodCall { target, arguments, .. } => {
+                let mut children : Vec<&Expression> = vec![target];
+                children.extend(arguments.iter());
+                children
+            },
+            ExpressionKind::Assign { target, value } => vec![target, value],
+            ExpressionKind::Declaration { value, .. } => vec![value],
+            ExpressionKind::StructUsage { values, .. } => values.iter().collect(),
+            ExpressionKind::Return(value) => vec![value],
+        }
+    }
+
+    //gathers the errors of all error nodes inside of this expression
+    pub fn collect_errors(&self, errors : &mut Vec<Error>){
+        if let ExpressionKind::Error(error) = &self.kind{
+            errors.push(error.clone());
+        }
+
+        for child in self.children(){
+            child.collect_errors(errors);
+        }
+    }
+}
+
+impl ExpressionKind{
+    pub fn spanned(self, span : Span) -> Expression{
+        Expression{
+            kind : self,
+            span
+        }
+    }
+
+    pub fn expr(self, span : Span) -> FallibleExpression{
+        return Ok(self.spanned(span))
     }
-    
 }
 
 pub type FallibleExpression = Result<Expression, Error>;
@@ -423,17 +970,22 @@ fn get_current_token(tokens : &Vec<Token>, current_index : &mut usize) -> Result
     if let Some(token) = tokens.get(*current_index){
         Ok(token.clone())
     } else {
-        Err(Error::Nil)
+        Err(end_of_file(tokens))
     }
 }
 
+//running out of tokens is reported where the file ends, which is the EOF token the lexer puts last
+fn end_of_file(tokens : &[Token]) -> Error{
+    Error::UnexpectedEof(tokens.last().map(|x| x.span).unwrap_or_default())
+}
+
 //checks if there is a next token, and returns it as well as consuming the current token
 fn consume_token(tokens : &Vec<Token>, current_index : &mut usize) -> Result<Token, Error>{
     if let Some(token) = tokens.get(*current_index + 1){
         *current_index += 1;
         Ok(token.clone())
     } else {
-        Err(Error::Nil)
+        Err(end_of_file(tokens))
     }
 }
 
@@ -463,14 +1015,36 @@ fn match_token(tokens : &Vec<Token>, current_index : &mut usize, check_token : T
 
 }
 
+//the span from the token at start up to (and including) the last consumed token, when nothing was
+//consumed (an empty file) it is the empty span where the current token starts
+fn span(tokens : &[Token], start : usize, current_index : &mut usize) -> Span{
+    let first = tokens.get(start).map(|x| x.span).unwrap_or_default();
+
+    match current_index.checked_sub(1).and_then(|last| tokens.get(last)) {
+        Some(last) if *current_index > start => first.to(last.span),
+        _ => Span{
+            end : first.start,
+            ..first
+        }
+    }
+}
+
+//looks one token ahead without consuming anything
+fn next_is(tokens : &[Token], current_index : &mut usize, check_token : TokenType) -> bool{
+    match tokens.get(*current_index + 1) {
+        Some(token) => token.check_against_token_type(check_token),
+        None => false
+    }
+}
+
 //instead of checking moving and and throwing, this function returns a bool *IF* the current token
 //matches
 fn match_tokens(tokens : &Vec<Token>, current_index : &mut usize, check_tokens : Vec<TokenType>) -> Result<bool, Error>{
 
-    if let Some(token) = tokens.get(*current_index){
+    if tokens.get(*current_index).is_some(){
         let token = get_current_token(tokens, current_index)?;
 
-        if check_tokens.iter().map(|x| token.check_against_token_type(x.clone())).any(|x| x == true){
+        if check_tokens.iter().any(|x| token.check_against_token_type(x.clone())){
            
             Ok(true)
 
@@ -479,143 +1053,261 @@ fn match_tokens(tokens : &Vec<Token>, current_index : &mut usize, check_tokens :
         }
 
     } else {
-        Err(Error::Nil)
+        Err(end_of_file(tokens))
     }
 
 }
 
 
-//helper function to construct left associative binary expressions
-fn binary(tokens : &Vec<Token>, current_index : &mut usize, matches : Vec<TokenType>, below : fn(&Vec<Token>, &mut usize) -> FallibleExpression) -> FallibleExpression {
-    let mut left = below(tokens, current_index)?;
-
-    while match_tokens(tokens, current_index, matches.clone())? {
-        let operator = get_current_token(tokens, current_index)?;
-        consume_token(tokens, current_index)?;
-
-
-        let right = below(tokens, current_index)?;
-
-        left = Expression::Binary{
-            left : Box::new(left),
-            operator,
-            right : Box::new(right)
-        }
-    }
-    
-
-    Ok(left)
-}
-
-//operator precedence is 
-//expression functions (things like if, else, while statements etc.)
-// =
-// ==/!=
-// and/or/xor 
-// >/>=/</<=
-// +/-
-// *// 
-// !/- 
-// FieldCall
-// literals/functions/arrays/objects
 fn expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
     
     match get_current_token(tokens, current_index)?.r#type {
         TokenType::LET => let_expr(tokens, current_index),
         TokenType::CONST => const_expr(tokens, current_index),
         TokenType::IF => if_expr(tokens, current_index),
+        TokenType::MATCH => match_expr(tokens, current_index),
         TokenType::WHILE => while_expr(tokens, current_index),
-        TokenType::TYPE => type_declaration(tokens, current_index),
+        TokenType::FOR => for_expr(tokens, current_index),
+        //type can also be the builtin function type(x), so we only declare when a name follows
+        TokenType::TYPE if next_is(tokens, current_index, TokenType::ID_) => type_declaration(tokens, current_index),
         TokenType::STRUCT => struct_declaration(tokens, current_index),
+        TokenType::ENUM => enum_declaration(tokens, current_index),
+        TokenType::IMPLBLOCK => impl_block(tokens, current_index),
         TokenType::FN => fn_expr(tokens, current_index),
         TokenType::OVERLOAD => overload_expr(tokens, current_index),
-        TokenType::CONTINUE => Expression::Continue.expr(),
-        TokenType::BREAK => Expression::Break.expr(),
+        TokenType::CONTINUE => {
+            consume_token(tokens, current_index)?;
+            ExpressionKind::Continue.expr(span(tokens, start, current_index))
+        },
+        TokenType::BREAK => {
+            consume_token(tokens, current_index)?;
+            ExpressionKind::Break.expr(span(tokens, start, current_index))
+        },
         TokenType::RETURN => return_expr(tokens, current_index),
-        _ => assign(tokens, current_index)
+        _ => operation(tokens, current_index)
     }
 
 }
 
 fn return_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
     consume_token(tokens, current_index)?;
 
     let returned = expr(tokens, current_index)?;
 
-    Expression::Return(Box::new(returned)).expr()
+    ExpressionKind::Return(Box::new(returned)).expr(span(tokens, start, current_index))
 }
 
 fn type_declaration(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
     consume_token(tokens, current_index)?;
 
     let name = get_current_token(tokens, current_index)?;
 
     match_token(tokens, current_index, TokenType::ID_)?;
+    let generics = generics(tokens, current_index)?;
     match_token(tokens, current_index, TokenType::EQ)?;
 
-    let associated_type = typed(tokens, current_index)?;
+    let associated_type = typed(tokens, current_index)?.parameterize(&generics);
 
 
-    Expression::TypeDeclaration{
+    ExpressionKind::TypeDeclaration{
         name,
-        r#type : associated_type    
-    }.expr()
+        generics,
+        r#type : associated_type,
+        docs : Vec::new()
+    }.expr(span(tokens, start, current_index))
 }
 
 fn struct_declaration(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
     consume_token(tokens, current_index)?;
 
     let name = get_current_token(tokens, current_index)?;
 
     match_token(tokens, current_index, TokenType::ID_)?;
-   
+    let generics = generics(tokens, current_index)?;
 
-    let r#type = object_typed(tokens, current_index)?; 
+    let r#type = object_typed(tokens, current_index)?.parameterize(&generics); 
 
 
-    Expression::StructDeclaration{
+    ExpressionKind::StructDeclaration{
         name,
-        r#type : r#type,
-    }.expr()
+        generics,
+        r#type,
+        docs : Vec::new()
+    }.expr(span(tokens, start, current_index))
 }
 
-fn overload_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+fn enum_declaration(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
     consume_token(tokens, current_index)?;
 
-    let mut operator = None;
+    let name = get_current_token(tokens, current_index)?;
 
-    if match_tokens(&tokens, current_index, vec![
-        TokenType::PLUS,
-        TokenType::MINUS,
-        TokenType::BANG,
-        TokenType::STAR,
-        TokenType::SLASH,
-        TokenType::GEQ,
-        TokenType::GE,
-        TokenType::LEQ,
-        TokenType::LE,
-        TokenType::EQEQ,
-        TokenType::NEQ,
-    ])?{
-        operator = Some(get_current_token(tokens, current_index)?);
-        consume_token(tokens, current_index)?;
-    }  
+    match_token(tokens, current_index, TokenType::ID_)?;
+    match_token(tokens, current_index, TokenType::LBRACE)?;
 
-    //this part is one to one correspondand with the function definitions
-    
-    match_token(tokens, current_index, TokenType::LPAREN)?;
+    let mut variants = Vec::new();
+    let mut payloads = Vec::new();
+    while !match_tokens(tokens, current_index, vec![TokenType::RBRACE])? {
+        let variant = get_current_token(tokens, current_index)?;
+        match_token(tokens, current_index, TokenType::ID_)?;
 
-    let mut arguments : Vec<TypedName> = vec![];
-    
+        let payload = match get_current_token(tokens, current_index)?.r#type {
+            TokenType::LPAREN => Payload::Tuple(tuple_typed(tokens, current_index)?),
+            //object_typed only ever gives back objects
+            TokenType::LBRACE => match object_typed(tokens, current_index)? {
+                Type::ObjectType { keys, types } => Payload::Fields{ keys, types },
+                _ => Payload::Unit
+            },
+            _ => Payload::Unit
+        };
 
-    while let Some(token) = tokens.get(*current_index){
-    
-        if let TokenType::RPAREN = token.r#type{
-            consume_token(tokens, current_index)?;
-            break; 
-        }
+        variants.push(variant.r#type.get_id_val().unwrap_or_default());
+        payloads.push(payload);
 
-        let argument = typed_primary(tokens, current_index)?;
+        //the comma is optional after the last variant
+        if !match_tokens(tokens, current_index, vec![TokenType::RBRACE])? {
+            match_token(tokens, current_index, TokenType::COMMA)?;
+        }
+    }
+    consume_token(tokens, current_index)?;
+
+    ExpressionKind::EnumDeclaration{
+        name,
+        r#type : Type::Enum{ variants, payloads },
+        docs : Vec::new()
+    }.expr(span(tokens, start, current_index))
+}
+
+//the types of a tuple variant: (num, string)
+fn tuple_typed(tokens : &Vec<Token>, current_index : &mut usize) -> Result<Vec<Type>, Error>{
+    match_token(tokens, current_index, TokenType::LPAREN)?;
+
+    let mut types = Vec::new();
+    while !match_tokens(tokens, current_index, vec![TokenType::RPAREN])? {
+        types.push(typed(tokens, current_index)?);
+
+        if match_tokens(tokens, current_index, vec![TokenType::RPAREN])? {
+            break;
+        }
+
+        match_token(tokens, current_index, TokenType::COMMA)?;
+        let current_token = get_current_token(tokens, current_index)?;
+        if current_token.r#type == TokenType::RPAREN{
+            return Err(Error::UnexpectedTokenOfMany{
+                expected : vec![],
+                unexpected : current_token
+            })
+        }
+    }
+    consume_token(tokens, current_index)?;
+
+    Ok(types)
+}
+
+fn impl_block(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
+    consume_token(tokens, current_index)?;
+
+    let struct_name = get_current_token(tokens, current_index)?;
+    let name = struct_name.r#type.get_id_val();
+
+    match_token(tokens, current_index, TokenType::ID_)?;
+    match_token(tokens, current_index, TokenType::LBRACE)?;
+
+    let mut functions = Vec::new();
+    while !match_tokens(tokens, current_index, vec![TokenType::RBRACE])? {
+        let docs = doc_comments(tokens, current_index);
+
+        let token = get_current_token(tokens, current_index)?;
+        if token.r#type != TokenType::FN {
+            return Err(Error::UnexpectedToken{
+                expected : TokenType::FN,
+                unexpected : token
+            })
+        }
+
+        //only named functions make sense in here, so we check for the name before fn_expr does
+        if !next_is(tokens, current_index, TokenType::ID_) {
+            return Err(Error::UnexpectedToken{
+                expected : TokenType::ID_,
+                unexpected : tokens[*current_index + 1].clone()
+            })
+        }
+
+        let mut function = attach_docs(fn_expr(tokens, current_index)?, docs);
+
+        //self does not need a type, it is always the struct the block is for
+        if let ExpressionKind::Fn { arguments, .. } = &mut function.kind {
+            if let Some(receiver) = arguments.first_mut().filter(|x| x.is_self() && !x.annotated){
+                receiver.r#type = Type::CustomType(name.clone().unwrap_or_default());
+                receiver.annotated = true;
+            }
+        }
+
+        terminate_statement(&function, tokens, current_index)?;
+        functions.push(function);
+    }
+    consume_token(tokens, current_index)?;
+
+    ExpressionKind::Impl{
+        struct_name,
+        functions
+    }.expr(span(tokens, start, current_index))
+}
+
+fn overload_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
+    consume_token(tokens, current_index)?;
+
+    let operators = vec![
+        TokenType::PLUS,
+        TokenType::MINUS,
+        TokenType::BANG,
+        TokenType::STAR,
+        TokenType::SLASH,
+        TokenType::PERCENT,
+        TokenType::STARSTAR,
+        TokenType::AMP,
+        TokenType::BAR,
+        TokenType::CARET,
+        TokenType::SHL,
+        TokenType::SHR,
+        TokenType::GEQ,
+        TokenType::GE,
+        TokenType::LEQ,
+        TokenType::LE,
+        TokenType::EQEQ,
+        TokenType::NEQ,
+    ];
+
+    let operator = get_current_token(tokens, current_index)?;
+    if !match_tokens(tokens, current_index, operators.clone())?{
+        return Err(Error::UnexpectedTokenOfMany{
+            expected : operators,
+            unexpected : operator
+        })
+    }
+    consume_token(tokens, current_index)?;
+
+    //this part is one to one correspondand with the function definitions
+    
+    match_token(tokens, current_index, TokenType::LPAREN)?;
+
+    let mut arguments : Vec<TypedName> = vec![];
+    
+
+    while let Some(token) = tokens.get(*current_index){
+    
+        if let TokenType::RPAREN = token.r#type{
+            consume_token(tokens, current_index)?;
+            break; 
+        }
+
+        let argument = typed_primary(tokens, current_index)?;
         arguments.push(argument);
     
         if match_tokens(tokens, current_index, vec![
@@ -636,31 +1328,25 @@ fn overload_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleEx
     }
 
 
-    let mut result_type : Option<Type> = None;
-    //maybe there is a type definition
-    if match_tokens(tokens, current_index, vec![
-        TokenType::ARROW
-    ])? {
-        consume_token(tokens, current_index)?;
-
-        result_type = Some(typed(tokens, current_index)?);
-
-    }
+    //unlike functions, overloads always say what they give back
+    match_token(tokens, current_index, TokenType::ARROW)?;
+    let result_type = typed(tokens, current_index)?;
 
 
-    let body = expr(tokens, current_index)?;
+    let body = body(tokens, current_index)?;
 
-    Ok(Expression::Overload{
-        operation : operator.unwrap(),
+    ExpressionKind::Overload{
+        operation : operator,
         arguments,
-        result : result_type.unwrap(),
+        result : result_type,
         body : Box::new(body)
-    })
+    }.expr(span(tokens, start, current_index))
     
 
 }
 
 fn fn_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
     consume_token(tokens, current_index)?; 
 
     let mut name = None;
@@ -672,6 +1358,7 @@ fn fn_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpressi
         consume_token(tokens, current_index)?;
     }
 
+    let generics = generics(tokens, current_index)?;
 
     match_token(tokens, current_index, TokenType::LPAREN)?;
 
@@ -717,17 +1404,25 @@ fn fn_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpressi
 
     }
 
-    let body = expr(tokens, current_index)?;
+    for argument in arguments.iter_mut(){
+        argument.r#type = argument.r#type.parameterize(&generics);
+    }
+    let result_type = result_type.map(|x| x.parameterize(&generics));
+
+    let body = body(tokens, current_index)?;
 
-    Ok(Expression::Fn{
+    ExpressionKind::Fn{
         arguments,
         name,
+        generics,
         result : result_type,
-        body : Box::new(body)
-    })
+        body : Box::new(body),
+        docs : Vec::new()
+    }.expr(span(tokens, start, current_index))
 }
     
 fn let_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
     consume_token(tokens, current_index)?;
     
     let name = typed_primary(tokens, current_index)?;
@@ -735,14 +1430,15 @@ fn let_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpress
 
     let value = expr(tokens, current_index)?;
 
-    Expression::Declaration{
+    ExpressionKind::Declaration{
         name,
         value : Box::new(value),
         constant : false
-    }.expr()
+    }.expr(span(tokens, start, current_index))
 }
 
 fn const_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
     consume_token(tokens, current_index)?;
   
     let name = typed_primary(tokens, current_index)?;
@@ -750,22 +1446,23 @@ fn const_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpre
 
     let value = expr(tokens, current_index)?;
 
-    Expression::Declaration{
+    ExpressionKind::Declaration{
         name,
         value : Box::new(value),
         constant : true
-    }.expr()
+    }.expr(span(tokens, start, current_index))
 }
 
 
 fn if_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
     consume_token(tokens, current_index)?;
 
     //match_token(tokens, current_index, TokenType::LPAREN)?;
     let condition = expr(tokens, current_index)?;
     //match_token(tokens, current_index, TokenType::RPAREN)?;
 
-    let if_block = expr(tokens, current_index)?;
+    let if_block = body(tokens, current_index)?;
     let mut else_if_blocks : Vec<(Expression, Expression)> = Vec::new();
     let mut else_block : Option<Box<Expression>> = None;
 
@@ -781,115 +1478,506 @@ fn if_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpressi
         if match_tokens(tokens, current_index, vec![
             TokenType::IF
         ])? {
-            //consume_token(tokens, current_index)?;
+            consume_token(tokens, current_index)?;
  
             
             let condition = expr(tokens, current_index)?;
 
 
             //match_token(tokens, current_index, TokenType::RPAREN)?;          
-            let block = expr(tokens, current_index)?;
+            let block = body(tokens, current_index)?;
 
             match_optional_token(tokens, current_index, TokenType::SEMICOLON)?;
             else_if_blocks.push((condition, block));
         } else {
-            else_block = Some(Box::new(expr(tokens, current_index)?));
+            else_block = Some(Box::new(body(tokens, current_index)?));
         }
     }
 
-    Expression::If{
+    ExpressionKind::If{
         condition : Box::new(condition),
         if_block : Box::new(if_block),
         else_if_blocks,
         else_block
-    }.expr()
+    }.expr(span(tokens, start, current_index))
+}
+
+fn match_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
+    consume_token(tokens, current_index)?;
+
+    let value = expr(tokens, current_index)?;
+    match_token(tokens, current_index, TokenType::LBRACE)?;
+
+    let mut arms = Vec::new();
+    while !match_tokens(tokens, current_index, vec![TokenType::RBRACE])? {
+        let pattern = pattern(tokens, current_index)?;
+
+        let mut guard = None;
+        if match_tokens(tokens, current_index, vec![TokenType::IF])? {
+            consume_token(tokens, current_index)?;
+            guard = Some(expr(tokens, current_index)?);
+        }
+
+        match_token(tokens, current_index, TokenType::IMPL)?;
+        let body = body(tokens, current_index)?;
+
+        //arms are separated by commas, which blocks (and the last arm) can leave out
+        let block = matches!(body.kind, ExpressionKind::Block { .. });
+        arms.push(MatchArm{ pattern, guard, body });
+
+        if match_tokens(tokens, current_index, vec![TokenType::RBRACE])? {
+            break;
+        }
+
+        if block {
+            match_optional_token(tokens, current_index, TokenType::COMMA)?;
+        } else {
+            match_token(tokens, current_index, TokenType::COMMA)?;
+        }
+    }
+    consume_token(tokens, current_index)?;
+
+    ExpressionKind::Match{
+        value : Box::new(value),
+        arms
+    }.expr(span(tokens, start, current_index))
+}
+
+fn pattern(tokens : &Vec<Token>, current_index : &mut usize) -> Result<Pattern, Error>{
+    let start = *current_index;
+    let token = get_current_token(tokens, current_index)?;
+
+    let kind = match &token.r#type {
+        TokenType::NUM(_) | TokenType::STR(_) | TokenType::TRUE | TokenType::FALSE | TokenType::NIL => {
+            PatternKind::Literal(primary(tokens, current_index)?)
+        },
+        //negative numbers
+        TokenType::MINUS => PatternKind::Literal(prefix(tokens, current_index)?),
+
+        TokenType::ID(name) => {
+            let name = name.clone();
+            consume_token(tokens, current_index)?;
+
+            match get_current_token(tokens, current_index)?.r#type {
+                TokenType::COLON => {
+                    consume_token(tokens, current_index)?;
+
+                    PatternKind::Typed{
+                        name : Some(name).filter(|x| x != "_"),
+                        r#type : typed(tokens, current_index)?
+                    }
+                },
+                TokenType::COLONCOLON => {
+                    consume_token(tokens, current_index)?;
+
+                    let variant = get_current_token(tokens, current_index)?;
+                    match_token(tokens, current_index, TokenType::ID_)?;
+
+                    let payload = match get_current_token(tokens, current_index)?.r#type {
+                        TokenType::LPAREN => PatternPayload::Tuple(tuple_patterns(tokens, current_index)?),
+                        TokenType::LBRACE => PatternPayload::Fields(field_patterns(tokens, current_index)?),
+                        _ => PatternPayload::Unit
+                    };
+
+                    PatternKind::Variant{
+                        enum_name : token,
+                        variant,
+                        payload
+                    }
+                },
+                TokenType::LBRACE => PatternKind::Struct{
+                    name : token,
+                    fields : field_patterns(tokens, current_index)?
+                },
+                _ if name == "_" => PatternKind::Wildcard,
+                _ => PatternKind::Binding(name)
+            }
+        },
+
+        TokenType::LBRACE => PatternKind::Object(field_patterns(tokens, current_index)?),
+
+        TokenType::LBRACK => {
+            consume_token(tokens, current_index)?;
+
+            let mut elements = Vec::new();
+            let mut rest = None;
+            while !match_tokens(tokens, current_index, vec![TokenType::RBRACK])? {
+                //the rest has to come last
+                if match_tokens(tokens, current_index, vec![TokenType::DOTDOTDOT])? {
+                    consume_token(tokens, current_index)?;
+                    rest = Some(Box::new(pattern(tokens, current_index)?));
+                    match_optional_token(tokens, current_index, TokenType::COMMA)?;
+                    break;
+                }
+
+                elements.push(pattern(tokens, current_index)?);
+
+                if !match_tokens(tokens, current_index, vec![TokenType::RBRACK])? {
+                    match_token(tokens, current_index, TokenType::COMMA)?;
+                }
+            }
+            match_token(tokens, current_index, TokenType::RBRACK)?;
+
+            PatternKind::Array{
+                elements,
+                rest
+            }
+        },
+
+        _ => return Err(Error::UnexpectedTokenOfMany{
+            expected : vec![TokenType::ID_, TokenType::NUM_, TokenType::STR_, TokenType::LBRACE, TokenType::LBRACK],
+            unexpected : token
+        })
+    };
+
+    Ok(Pattern{
+        kind,
+        span : span(tokens, start, current_index)
+    })
+}
+
+//the patterns of a tuple variant: (a, _, 0)
+fn tuple_patterns(tokens : &Vec<Token>, current_index : &mut usize) -> Result<Vec<Pattern>, Error>{
+    match_token(tokens, current_index, TokenType::LPAREN)?;
+
+    let mut patterns = Vec::new();
+    while !match_tokens(tokens, current_index, vec![TokenType::RPAREN])? {
+        patterns.push(pattern(tokens, current_index)?);
+
+        if !match_tokens(tokens, current_index, vec![TokenType::RPAREN])? {
+            match_token(tokens, current_index, TokenType::COMMA)?;
+        }
+    }
+    consume_token(tokens, current_index)?;
+
+    Ok(patterns)
+}
+
+//the fields of struct, object and variant patterns: { x, y : 0 }, the shorthand x is the same as x : x
+fn field_patterns(tokens : &Vec<Token>, current_index : &mut usize) -> Result<Vec<(Token, Pattern)>, Error>{
+    match_token(tokens, current_index, TokenType::LBRACE)?;
+
+    let mut fields = Vec::new();
+    while !match_tokens(tokens, current_index, vec![TokenType::RBRACE])? {
+        let key = get_current_token(tokens, current_index)?;
+        match_token(tokens, current_index, TokenType::ID_)?;
+
+        let value = if match_tokens(tokens, current_index, vec![TokenType::COLON])? {
+            consume_token(tokens, current_index)?;
+            pattern(tokens, current_index)?
+        } else {
+            Pattern{
+                kind : PatternKind::Binding(key.r#type.get_id_val().unwrap_or_default()),
+                span : key.span
+            }
+        };
+        fields.push((key, value));
+
+        if !match_tokens(tokens, current_index, vec![TokenType::RBRACE])? {
+            match_token(tokens, current_index, TokenType::COMMA)?;
+        }
+    }
+    consume_token(tokens, current_index)?;
+
+    Ok(fields)
+}
+
+//the body of an if, a loop, a function or a match arm and every statement, where {} is an empty block
+//instead of an empty object
+fn body(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
+
+    if match_tokens(tokens, current_index, vec![TokenType::LBRACE])? && next_is(tokens, current_index, TokenType::RBRACE) {
+        consume_token(tokens, current_index)?;
+        consume_token(tokens, current_index)?;
+        return ExpressionKind::Block{
+            expressions : vec![]
+        }.expr(span(tokens, start, current_index))
+    }
+
+    expr(tokens, current_index)
 }
 
 fn while_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
     consume_token(tokens, current_index)?;
 
     let condition = expr(tokens, current_index)?;
 
-    let block = expr(tokens, current_index)?;
+    let block = body(tokens, current_index)?;
 
-    Expression::While{
+    ExpressionKind::While{
         condition : Box::new(condition),
         block : Box::new(block)
-    }.expr()
+    }.expr(span(tokens, start, current_index))
 }
 
 fn for_expr(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{ 
-    todo!()
+    let start = *current_index;
+    consume_token(tokens, current_index)?;
+
+    let binding = typed_primary(tokens, current_index)?;
+
+    let range = match get_current_token(tokens, current_index)?.r#type {
+        //for x in xs
+        TokenType::IN => {
+            consume_token(tokens, current_index)?;
+
+            let iterable = expr(tokens, current_index)?;
+
+            ForRange::In(Box::new(iterable))
+        },
+        //for i = start to end (step n)
+        TokenType::EQ => {
+            consume_token(tokens, current_index)?;
+
+            let start = expr(tokens, current_index)?;
+            match_token(tokens, current_index, TokenType::TO)?;
+            let end = expr(tokens, current_index)?;
+
+            let mut step = None;
+            if match_tokens(tokens, current_index, vec![
+                TokenType::STEP
+            ])? {
+                consume_token(tokens, current_index)?;
+                step = Some(Box::new(expr(tokens, current_index)?));
+            }
+
+            ForRange::To{
+                start : Box::new(start),
+                end : Box::new(end),
+                step
+            }
+        },
+        _ => {
+            return Err(Error::UnexpectedTokenOfMany{
+                expected : vec![TokenType::IN, TokenType::EQ],
+                unexpected : get_current_token(tokens, current_index)?
+            })
+        }
+    };
+
+    let block = body(tokens, current_index)?;
+
+    ExpressionKind::For{
+        binding,
+        range,
+        block : Box::new(block)
+    }.expr(span(tokens, start, current_index))
 }
 
 
-fn assign(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{    
-    binary(tokens, current_index, vec![
-        TokenType::EQ,
-    ], equality)   
+//operators are parsed with a pratt parser, every operator has a precedence and an associativity and
+//the loop in operation keeps taking operators as long as they bind at least as tight as what we are
+//currently parsing. from loosest to tightest:
+//
+// = += -= *= /=    right      a = b = c is a = (b = c)
+// or               left
+// xor              left
+// and              left
+// == !=            left
+// < > <= >= is     left       the right side of is is a type
+// |                left
+// ^                left
+// &                left
+// << >>            left
+// + -              left
+// * / %            left
+// ! - *            prefix
+// **               right      -2 ** 2 is -(2 ** 2), 2 ** 3 ** 2 is 2 ** (3 ** 2)
+// calls, fields and indices (see call)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence{
+    Assignment = 1,
+    Or,
+    Xor,
+    And,
+    Equality,
+    Comparison,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shift,
+    Term,
+    Factor,
+    Prefix,
+    Power,
 }
 
-fn equality(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{    
-    binary(tokens, current_index, vec![
-        TokenType::EQEQ,
-        TokenType::NEQ,
-    ], logical)   
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Associativity{
+    Left,
+    Right
 }
 
-fn logical(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{    
-    binary(tokens, current_index, vec![
-        TokenType::XOR,
-        TokenType::OR,
-        TokenType::AND,
-    ], comp) 
+//the precedence of an operator that comes between two expressions, none if the token is not one
+fn infix_precedence(token_type : &TokenType) -> Option<(Precedence, Associativity)>{
+    let precedence = match token_type {
+        TokenType::EQ
+            | TokenType::PLUSEQ
+            | TokenType::MINUSEQ
+            | TokenType::STAREQ
+            | TokenType::SLASHEQ => return Some((Precedence::Assignment, Associativity::Right)),
+        TokenType::STARSTAR => return Some((Precedence::Power, Associativity::Right)),
+
+        TokenType::OR => Precedence::Or,
+        TokenType::XOR => Precedence::Xor,
+        TokenType::AND => Precedence::And,
+        TokenType::EQEQ | TokenType::NEQ => Precedence::Equality,
+        TokenType::GE | TokenType::GEQ | TokenType::LE | TokenType::LEQ | TokenType::IS => Precedence::Comparison,
+        TokenType::BAR => Precedence::BitOr,
+        TokenType::CARET => Precedence::BitXor,
+        TokenType::AMP => Precedence::BitAnd,
+        TokenType::SHL | TokenType::SHR => Precedence::Shift,
+        TokenType::PLUS | TokenType::MINUS => Precedence::Term,
+        TokenType::STAR | TokenType::SLASH | TokenType::PERCENT => Precedence::Factor,
+        _ => return None
+    };
+
+    Some((precedence, Associativity::Left))
 }
 
-fn comp(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{    
-    binary(tokens, current_index, vec![
-        TokenType::GEQ,
-        TokenType::GE,
-        TokenType::LEQ,
-        TokenType::LE,
-    ], term)
+//only variables, fields and indices can be assigned to
+fn is_assignable(expression : &Expression) -> bool{
+    matches!(expression.kind, ExpressionKind::LiteralID(_) | ExpressionKind::FieldAccess { .. } | ExpressionKind::Index { .. })
 }
 
-fn term(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
-    binary(tokens, current_index, vec![
-        TokenType::PLUS,
-        TokenType::MINUS,
-    ], factor)
+fn operation(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    operation_above(tokens, current_index, Precedence::Assignment)
 }
 
+//parses an expression made of operators that bind at least as tight as minimum
+fn operation_above(tokens : &Vec<Token>, current_index : &mut usize, minimum : Precedence) -> FallibleExpression{
+    let mut left = prefix(tokens, current_index)?;
+
+    loop {
+        let operator = get_current_token(tokens, current_index)?;
+
+        let (precedence, associativity) = match infix_precedence(&operator.r#type) {
+            Some(binding) if binding.0 >= minimum => binding,
+            _ => break
+        };
+        consume_token(tokens, current_index)?;
 
+        if precedence == Precedence::Assignment && !is_assignable(&left) {
+            return Err(Error::InvalidAssignmentTarget(left.span))
+        }
 
+        if operator.r#type == TokenType::IS {
+            let type_start = *current_index;
+            let r#type = typed(tokens, current_index)?;
 
-fn factor(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
-    binary(tokens, current_index, vec![
-        TokenType::STAR,
-        TokenType::SLASH,
-    ],unary)
+            let span = left.span.to(span(tokens, type_start, current_index));
+            left = ExpressionKind::Is{
+                value : Box::new(left),
+                r#type
+            }.spanned(span);
+            continue;
+        }
+
+        //a left associative operator does not take another one of its own level on the right side,
+        //which is what makes a - b - c into (a - b) - c
+        let right = match associativity {
+            Associativity::Right => operation_above(tokens, current_index, precedence)?,
+            Associativity::Left => operation_above(tokens, current_index, tighter(precedence))?
+        };
+
+        let span = left.span.to(right.span);
+        left = match operator.r#type {
+            TokenType::EQ => ExpressionKind::Assign{
+                target : Box::new(left),
+                value : Box::new(right)
+            },
+            _ => ExpressionKind::Binary{
+                left : Box::new(left),
+                operator,
+                right : Box::new(right)
+            }
+        }.spanned(span)
+    }
+
+    Ok(left)
 }
 
-fn unary(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+//the level right above the given one
+fn tighter(precedence : Precedence) -> Precedence{
+    match precedence {
+        Precedence::Assignment => Precedence::Or,
+        Precedence::Or => Precedence::Xor,
+        Precedence::Xor => Precedence::And,
+        Precedence::And => Precedence::Equality,
+        Precedence::Equality => Precedence::Comparison,
+        Precedence::Comparison => Precedence::BitOr,
+        Precedence::BitOr => Precedence::BitXor,
+        Precedence::BitXor => Precedence::BitAnd,
+        Precedence::BitAnd => Precedence::Shift,
+        Precedence::Shift => Precedence::Term,
+        Precedence::Term => Precedence::Factor,
+        Precedence::Factor => Precedence::Prefix,
+        Precedence::Prefix | Precedence::Power => Precedence::Power,
+    }
+}
+
+//prefix operators take everything that binds tighter than them, which is only **
+fn prefix(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
     if match_tokens(tokens, current_index, vec![
             TokenType::STAR, 
-            TokenType::BANG
+            TokenType::BANG,
+            TokenType::MINUS
     ])? {
         let operator = get_current_token(tokens, current_index)?;
         consume_token(tokens, current_index)?;
 
-        let right = unary(tokens, current_index)?;
+        let right = operation_above(tokens, current_index, Precedence::Power)?;
 
-        return Ok(Expression::Unary{
+        let span = operator.span.to(right.span);
+        return ExpressionKind::Unary{
             operator,
             right : Box::new(right)
-        })
+        }.expr(span)
     }
 
     call(tokens, current_index)
 }
 
+//the arguments of a call, starting at the (
+fn call_arguments(tokens : &Vec<Token>, current_index : &mut usize) -> Result<Vec<Expression>, Error>{
+    match_token(tokens, current_index, TokenType::LPAREN)?;
+    
+    let mut arguments : Vec<Expression> = Vec::new();
+    while let Some(token) = tokens.get(*current_index){
+        //calls without any arguments
+        if token.r#type == TokenType::RPAREN && arguments.is_empty(){
+            consume_token(tokens, current_index)?;
+            break;
+        }
+
+        let argument = expr(tokens, current_index)?; 
+        arguments.push(argument);
+ 
+        //check if we encountered the closing brackets
+        if get_current_token(tokens, current_index)?.r#type == TokenType::RPAREN{
+            consume_token(tokens, current_index)?;
+            break;
+        }                   
+
+        match_token(tokens, current_index, TokenType::COMMA)?;
+        let current_token = get_current_token(tokens, current_index)?;
+        //check if we have a trailing comma
+        if current_token.r#type == TokenType::RPAREN{
+            return Err(Error::UnexpectedTokenOfMany{
+                expected : vec![], 
+                unexpected : current_token
+            })
+        }
+    }
+
+    Ok(arguments)
+}
+
 fn call(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
     let mut left = primary(tokens, current_index)?;
 
    
@@ -901,64 +1989,46 @@ fn call(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
 
         match get_current_token(tokens, current_index)?.r#type {
             TokenType::DOT => {
-                let operator = get_current_token(tokens, current_index)?;
                 consume_token(tokens, current_index)?;
     
-
-                //we use primary instead of expr because DOT cannot be used for something like: 
-                //4.(3 + 4)
-                //thats what the [] operator is for
-                let right = primary(tokens, current_index)?;
-                left = Expression::Binary{
-                    left : Box::new(left), 
-                    operator,
-                    right :  Box::new(right)
+                //only a name can come after the dot, computed fields are what the [] operator is for
+                let field = get_current_token(tokens, current_index)?;
+                match_token(tokens, current_index, TokenType::ID_)?;
+
+                if match_tokens(tokens, current_index, vec![TokenType::LPAREN])? {
+                    let arguments = call_arguments(tokens, current_index)?;
+
+                    left = ExpressionKind::MethodCall{
+                        target : Box::new(left),
+                        method : field,
+                        arguments
+                    }.spanned(span(tokens, start, current_index))
+                } else {
+                    left = ExpressionKind::FieldAccess{
+                        target : Box::new(left), 
+                        field
+                    }.spanned(span(tokens, start, current_index))
                 }
             },
             TokenType::LBRACK => {
-               
-                let operator = get_current_token(tokens, current_index)?;
                 consume_token(tokens, current_index)?;
 
-                let right = expr(tokens, current_index)?;
+                let index = expr(tokens, current_index)?;
 
                 match_token(tokens, current_index, TokenType::RBRACK)?;
 
-                left = Expression::Binary{
-                    left : Box::new(left),
-                    operator,
-                    right : Box::new(right)
-                }
+                left = ExpressionKind::Index{
+                    target : Box::new(left),
+                    index : Box::new(index)
+                }.spanned(span(tokens, start, current_index))
             },
             TokenType::LPAREN => {
-                consume_token(tokens, current_index)?;
-                
-                let mut arguments : Vec<Expression> = Vec::new();
-                while let Some(token) = tokens.get(*current_index){
-                    let argument = expr(tokens, current_index)?; 
-                    arguments.push(argument);
- 
-                    //check if we encountered the closing brackets
-                    if get_current_token(tokens, current_index)?.r#type == TokenType::RPAREN{
-                        consume_token(tokens, current_index)?;
-                        break;
-                    }                   
-
-                    match_token(tokens, current_index, TokenType::COMMA)?;
-                    let current_token = get_current_token(tokens, current_index)?;
-                    //check if we have a trailing comma
-                    if current_token.r#type == TokenType::RPAREN{
-                        return Err(Error::UnexpectedTokenOfMany{
-                            expected : vec![], 
-                            unexpected : current_token
-                        })
-                    }
-                }
+                let arguments = call_arguments(tokens, current_index)?;
 
-                left = Expression::FunctionCall{
+                left = ExpressionKind::FunctionCall{
                     function : Box::new(left),
                     arguments 
-                }
+                }.spanned(span(tokens, start, current_index))
             }
             _ => ()
         }
@@ -972,8 +2042,14 @@ fn call(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
 
 
 fn primary(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+    let start = *current_index;
    
     let token = get_current_token(tokens, current_index)?;
+
+    //there is nothing after the end of the file to consume
+    if token.r#type == TokenType::EOF {
+        return Err(Error::ExpectedExpression(token))
+    }
     consume_token(tokens, current_index)?;
     match &token.r#type{
         
@@ -982,15 +2058,36 @@ fn primary(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpressi
             //clone the name for clean borrow;
             let name = name.clone();
             let struct_name = token.clone();
-            if match_tokens(tokens, current_index, vec![
-                TokenType::LBRACE
-            ])?{
+            let mut variant = None;
+
+            if match_tokens(tokens, current_index, vec![TokenType::COLONCOLON])? {
+                consume_token(tokens, current_index)?;
+
+                let function = get_current_token(tokens, current_index)?;
+                match_token(tokens, current_index, TokenType::ID_)?;
+
+                //Name::Variant { ... } builds a variant of an enum that has fields
+                if !is_struct_usage(tokens, current_index)? {
+                    return ExpressionKind::Associated{ struct_name, function }.expr(span(tokens, start, current_index))
+                }
+                variant = Some(function);
+            }
+
+            if is_struct_usage(tokens, current_index)?{
                 //this means that we are using a struct
                 consume_token(tokens, current_index)?; 
 
                 let mut fields = vec![];
                 let mut values = vec![];
-                while let Some(token) = tokens.get(*current_index){
+                while tokens.get(*current_index).is_some(){
+
+                    //this also ends structs without fields (Name {}) and a trailing comma
+                    if match_tokens(tokens, current_index, vec![
+                        TokenType::RBRACE,
+                    ])? {
+                        consume_token(tokens, current_index)?;
+                        return ExpressionKind::StructUsage{ struct_name, variant, fields, values}.expr(span(tokens, start, current_index))
+                    }
                    
                     let field_name = get_current_token(tokens, current_index)?;
                     match_token(tokens, current_index, TokenType::ID_)?;
@@ -999,27 +2096,13 @@ fn primary(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpressi
                     match_token(tokens, current_index, TokenType::COLON)?; 
                     let value = expr(tokens, current_index)?;
                     values.push(value);
-                    
-               
-                
-                    //check if we have a comma (could in theory be voluntary)
-                    let mut comma_used = false;
-                    if match_tokens(tokens, current_index, vec![
-                        TokenType::COMMA,
-                    ])? {
-                        comma_used = true;
-                        consume_token(tokens, current_index)?;
-                    }
 
+                    //fields are separated by commas, only the last one can leave it out
                     if match_tokens(tokens, current_index, vec![
-                        TokenType::RBRACE,
+                        TokenType::COMMA,
                     ])? {
                         consume_token(tokens, current_index)?;
-                        return Expression::StructUsage{ struct_name, fields, values}.expr()
-                    }
-                   
-                    //if no comma was used so far we check again for one to make sure
-                    if !comma_used{
+                    } else if !match_tokens(tokens, current_index, vec![TokenType::RBRACE])? {
                         match_token(tokens, current_index, TokenType::COMMA)?;
                     }
 
@@ -1027,28 +2110,63 @@ fn primary(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpressi
 
             }
 
-            Expression::LiteralID(name).expr()
+            ExpressionKind::LiteralID(name).expr(span(tokens, start, current_index))
         },
         TokenType::NUM(number) =>{
+            let number = Number::parse(number).ok_or(Error::NumberOutOfRange{
+                number : number.to_string(),
+                span : token.span
+            })?;
 
-            //we break into two so we can do comparisons with hashmaps
-            let integral = number.split(".").nth(0).unwrap().parse::<u64>().unwrap();
-            let fractional = match number.split(".").nth(1) {
-                Some(part) => part.parse::<u64>().unwrap(),
-                None => 0
-            };
-                
-
-            Expression::LiteralNum(integral, fractional).expr()
+            ExpressionKind::LiteralNum(number).expr(span(tokens, start, current_index))
         },
         TokenType::STR(string) => {
-            Expression::LiteralStr(string.to_string()).expr()
+            ExpressionKind::LiteralStr(string.to_string()).expr(span(tokens, start, current_index))
+        },
+        TokenType::STRHEAD(head) => {
+            let mut parts = Vec::new();
+
+            //empty pieces of text (like between two interpolations) are left out
+            if !head.is_empty() {
+                parts.push(ExpressionKind::LiteralStr(head.to_string()).spanned(token.span));
+            }
+
+            loop {
+                parts.push(expr(tokens, current_index)?);
+
+                let piece = get_current_token(tokens, current_index)?;
+                let (text, done) = match &piece.r#type {
+                    TokenType::STRMID(text) => (text, false),
+                    TokenType::STRTAIL(text) => (text, true),
+                    _ => return Err(Error::UnexpectedToken{
+                        expected : TokenType::RBRACE,
+                        unexpected : piece
+                    })
+                };
+                consume_token(tokens, current_index)?;
+
+                if !text.is_empty() {
+                    parts.push(ExpressionKind::LiteralStr(text.to_string()).spanned(piece.span));
+                }
+
+                if done {
+                    break;
+                }
+            }
+
+            ExpressionKind::Interpolated(parts).expr(span(tokens, start, current_index))
         },
         TokenType::TRUE => {
-            Expression::LiteralBool(true).expr()
+            ExpressionKind::LiteralBool(true).expr(span(tokens, start, current_index))
         },
         TokenType::FALSE => {
-            Expression::LiteralBool(false).expr()
+            ExpressionKind::LiteralBool(false).expr(span(tokens, start, current_index))
+        },
+        TokenType::NIL => {
+            ExpressionKind::LiteralNil.expr(span(tokens, start, current_index))
+        },
+        TokenType::TYPE => {
+            ExpressionKind::LiteralID("type".to_string()).expr(span(tokens, start, current_index))
         },
 
 
@@ -1056,6 +2174,12 @@ fn primary(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpressi
         TokenType::LBRACK => {
             let mut literals : Vec<Expression> = Vec::new();
             while let Some(token) = tokens.get(*current_index){
+                //empty arrays
+                if token.r#type == TokenType::RBRACK && literals.is_empty(){
+                    consume_token(tokens, current_index)?;
+                    break;
+                }
+
                 let literal = expr(tokens, current_index)?;
                 
                 literals.push(literal);
@@ -1076,7 +2200,7 @@ fn primary(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpressi
                 
             } 
 
-            Ok(Expression::LiteralArray(literals))
+            ExpressionKind::LiteralArray(literals).expr(span(tokens, start, current_index))
         }, 
 
         //objects
@@ -1084,19 +2208,24 @@ fn primary(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpressi
         TokenType::LBRACE => {
             
 
-            //we check for an rbrace if we find one we immediately return 
+            if is_object_literal(tokens, current_index) {
+                return object_literal(tokens, current_index, start)
+            }
+
+            //{} where a value is expected is an empty object, bodies and statements parse it as a block
             if match_tokens(tokens, current_index, vec![TokenType::RBRACE])? {
                 consume_token(tokens, current_index)?;
-                return Expression::Block{
-                    expressions : vec![]
-                }.expr();
+                return ExpressionKind::LiteralObject(vec![]).expr(span(tokens, start, current_index));
             };
 
 
 
              
-                 
-            block(tokens, current_index)     
+            //the block starts inside of the braces, but we want the braces to be part of its span
+            let mut block = block(tokens, current_index, true)?;
+            block.span = span(tokens, start, current_index);
+
+            Ok(block)
         
 
         },
@@ -1105,9 +2234,9 @@ fn primary(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpressi
             let expression = expr(tokens, current_index)?;
             match_token(tokens, current_index, TokenType::RPAREN)?;  
 
-            Ok(Expression::Grp{
+            ExpressionKind::Grp{
                 inner : Box::new(expression)
-            })
+            }.expr(span(tokens, start, current_index))
         },
 
 
@@ -1115,60 +2244,327 @@ fn primary(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpressi
         
 
         _ => {
-            Err(Error::Nil) 
+            Err(Error::ExpectedExpression(token))
+        }
+    }
+
+}
+
+//after a { we have an object if it starts with a field (key :), a spread (...) or a shorthand field
+//({ x, ... } or { x }), none of which can start a block
+fn is_object_literal(tokens : &[Token], current_index : &mut usize) -> bool{
+    let first = tokens.get(*current_index).map(|token| token.r#type.ignore_value());
+    let second = tokens.get(*current_index + 1).map(|token| token.r#type.clone());
+
+    matches!((first, second),
+        (Some(TokenType::DOTDOTDOT), _)
+            | (Some(TokenType::ID_ | TokenType::STR_), Some(TokenType::COLON))
+            | (Some(TokenType::ID_), Some(TokenType::COMMA | TokenType::RBRACE)))
+}
+
+//parses the entries of an object literal, the { is already consumed
+fn object_literal(tokens : &Vec<Token>, current_index : &mut usize, start : usize) -> FallibleExpression{
+    let mut entries = Vec::new();
+
+    loop {
+        if match_tokens(tokens, current_index, vec![TokenType::RBRACE])? {
+            consume_token(tokens, current_index)?;
+            break;
+        }
+
+        let token = get_current_token(tokens, current_index)?;
+
+        match &token.r#type {
+            TokenType::DOTDOTDOT => {
+                consume_token(tokens, current_index)?;
+                entries.push(ObjectEntry::Spread{
+                    operator : token,
+                    value : expr(tokens, current_index)?
+                });
+            },
+            TokenType::ID(name) => {
+                consume_token(tokens, current_index)?;
+
+                let value = if match_tokens(tokens, current_index, vec![TokenType::COLON])? {
+                    consume_token(tokens, current_index)?;
+                    expr(tokens, current_index)?
+                } else {
+                    ExpressionKind::LiteralID(name.to_string()).spanned(token.span)
+                };
+
+                entries.push(ObjectEntry::Field{
+                    key : token,
+                    value
+                });
+            },
+            TokenType::STR(_) => {
+                consume_token(tokens, current_index)?;
+                match_token(tokens, current_index, TokenType::COLON)?;
+
+                entries.push(ObjectEntry::Field{
+                    key : token,
+                    value : expr(tokens, current_index)?
+                });
+            },
+            _ => return Err(Error::UnexpectedTokenOfMany{
+                expected : vec![TokenType::ID_, TokenType::STR_, TokenType::DOTDOTDOT, TokenType::RBRACE],
+                unexpected : token
+            })
+        }
+
+        //entries are separated by commas, a trailing one is fine
+        if match_tokens(tokens, current_index, vec![TokenType::COMMA])? {
+            consume_token(tokens, current_index)?;
+        } else if !match_tokens(tokens, current_index, vec![TokenType::RBRACE])? {
+            return Err(Error::UnexpectedTokenOfMany{
+                expected : vec![TokenType::COMMA, TokenType::RBRACE],
+                unexpected : get_current_token(tokens, current_index)?
+            })
+        }
+    }
+
+    ExpressionKind::LiteralObject(entries).expr(span(tokens, start, current_index))
+}
+
+//how many tokens after `name { field :` are looked at to tell a struct usage from a match arm
+const STRUCT_LOOKAHEAD : usize = 6;
+
+//a name followed by a brace is only a struct usage if the brace opens with a field (name { field :),
+//otherwise constructs like "for x in xs { ... }" or "while running { ... }" would be parsed as structs.
+//empty braces only build a struct after a capitalized name (Name {}), since "while running {}" is a loop.
+//the arms of a match can look like fields too (match x { n : num => ...}), but their type is followed by
+//a => where a field value is followed by a comma or the closing brace. we only look a few tokens ahead
+//for that, a match on a bare name with an arm type longer than that needs parentheses: match (x) { ... }
+fn is_struct_usage(tokens : &Vec<Token>, current_index : &mut usize) -> Result<bool, Error>{
+    if !match_tokens(tokens, current_index, vec![TokenType::LBRACE])? {
+        return Ok(false)
+    }
+
+    if next_is(tokens, current_index, TokenType::RBRACE) {
+        //the name is the token right in front of the brace
+        let name = tokens[*current_index - 1].r#type.get_id_val().unwrap_or_default();
+        return Ok(name.starts_with(|x : char| x.is_uppercase()))
+    }
+
+    let field = tokens.get(*current_index + 1).map(|token| token.check_against_token_type(TokenType::ID_));
+    let colon = tokens.get(*current_index + 2).map(|token| token.check_against_token_type(TokenType::COLON));
+
+    if field != Some(true) || colon != Some(true) {
+        return Ok(false)
+    }
+
+    let mut depth = 0;
+    for token in tokens.iter().skip(*current_index + 3).take(STRUCT_LOOKAHEAD){
+        match token.r#type {
+            TokenType::LBRACE | TokenType::LBRACK | TokenType::LPAREN => depth += 1,
+            TokenType::RBRACE | TokenType::RBRACK | TokenType::RPAREN if depth > 0 => depth -= 1,
+            TokenType::COMMA | TokenType::RBRACE if depth == 0 => return Ok(true),
+            TokenType::IMPL if depth == 0 => return Ok(false),
+            _ => ()
+        }
+    }
+
+    Ok(true)
+}
+
+//statements have to end with a semicolon, except for the ones that end in a block anyway
+fn terminate_statement(expression : &Expression, tokens : &Vec<Token>, current_index : &mut usize) -> Result<(), Error>{
+    match &expression.kind {
+        ExpressionKind::StructDeclaration { .. }
+            | ExpressionKind::EnumDeclaration { .. }
+            | ExpressionKind::Impl { .. }
+            | ExpressionKind::Block { .. }
+            | ExpressionKind::If { .. }
+            | ExpressionKind::Match { .. }
+            | ExpressionKind::While { .. }
+            | ExpressionKind::For { .. } => match_optional_token(tokens, current_index, TokenType::SEMICOLON),
+        ExpressionKind::Fn { body, .. } | ExpressionKind::Overload { body, .. } => {
+            if let ExpressionKind::Block { .. } = body.kind {
+                match_optional_token(tokens, current_index, TokenType::SEMICOLON)
+            } else {
+                match_token(tokens, current_index, TokenType::SEMICOLON)?;
+                Ok(())
+            }
+        },
+        _ => {
+            match_token(tokens, current_index, TokenType::SEMICOLON)?;
+            Ok(())
+        }
+    }
+}
+
+//panic mode recovery: after an error we skip tokens until something looks like the start of the next
+//statement, semicolons are consumed while closing braces are left for the block they belong to.
+//blocks opened after the error (the body of for 5 in x { }) are skipped as a whole
+fn synchronize(tokens : &[Token], current_index : &mut usize, statement_start : usize){
+    let mut depth = 0;
+
+    while let Some(token) = tokens.get(*current_index){
+        match token.r#type {
+            TokenType::EOF => return,
+            TokenType::LBRACE => {
+                depth += 1;
+                *current_index += 1;
+            },
+            TokenType::RBRACE if depth == 0 => return,
+            TokenType::RBRACE => {
+                depth -= 1;
+                *current_index += 1;
+
+                if depth == 0 {
+                    return
+                }
+            },
+            _ if depth > 0 => *current_index += 1,
+
+            TokenType::SEMICOLON => {
+                *current_index += 1;
+                return
+            },
+
+            //the statement that failed might start with one of these, so we have to move past it first
+            TokenType::LET
+                | TokenType::CONST
+                | TokenType::FN
+                | TokenType::STRUCT
+                | TokenType::ENUM
+                | TokenType::IMPLBLOCK
+                | TokenType::TYPE
+                | TokenType::OVERLOAD
+                | TokenType::IF
+                | TokenType::MATCH
+                | TokenType::WHILE if *current_index > statement_start => return,
+
+            _ => *current_index += 1
+        }
+    }
+}
+
+//consumes the doc comments in front of a statement
+fn doc_comments(tokens : &[Token], current_index : &mut usize) -> Vec<Token>{
+    let mut docs = Vec::new();
+
+    while let Some(token) = tokens.get(*current_index){
+        if let TokenType::DOC(_) = token.r#type {
+            docs.push(token.clone());
+            *current_index += 1;
+        } else {
+            break;
         }
     }
 
+    docs
+}
+
+//only declarations keep their docs, on anything else they are dropped
+fn attach_docs(mut expression : Expression, docs : Vec<Token>) -> Expression{
+    match &mut expression.kind {
+        ExpressionKind::Fn { docs : attached, .. }
+            | ExpressionKind::StructDeclaration { docs : attached, .. }
+            | ExpressionKind::EnumDeclaration { docs : attached, .. }
+            | ExpressionKind::TypeDeclaration { docs : attached, .. } => *attached = docs,
+        _ => ()
+    }
+
+    expression
 }
 
-fn block(tokens : &Vec<Token>, current_index : &mut usize) -> FallibleExpression{
+//doc comments are only kept where a declaration can follow them: at the start of a statement and in
+//front of fn, struct, enum or type, everywhere else (like inside of struct bodies) they are plain comments
+fn strip_misplaced_docs(tokens : Vec<Token>) -> Vec<Token>{
+    let mut kept : Vec<Token> = Vec::new();
+
+    for (index, token) in tokens.iter().enumerate(){
+        if let TokenType::DOC(_) = token.r#type {
+            let previous = kept.iter().rev().find(|x| !matches!(x.r#type, TokenType::DOC(_)));
+            let next = tokens[index..].iter().find(|x| !matches!(x.r#type, TokenType::DOC(_)));
+
+            let statement_start = match previous {
+                None => true,
+                Some(previous) => matches!(previous.r#type, TokenType::SEMICOLON | TokenType::LBRACE | TokenType::RBRACE)
+            };
+            let declaration = match next {
+                Some(next) => matches!(next.r#type, TokenType::FN | TokenType::STRUCT | TokenType::ENUM | TokenType::TYPE),
+                None => false
+            };
+
+            if !(statement_start && declaration) {
+                continue;
+            }
+        }
+
+        kept.push(token.clone());
+    }
+
+    kept
+}
+
+//parses statements until the closing brace, or the end of the file for the top level (braced is false)
+//errors do not stop the block, they are kept as error nodes and parsing continues with the next statement
+fn block(tokens : &Vec<Token>, current_index : &mut usize, braced : bool) -> FallibleExpression{
+    let start = *current_index;
 
     let mut expressions = Vec::new();
 
     while let Some(token) = tokens.get(*current_index){
         if let TokenType::EOF = token.r#type  {
-            return Expression::Block{
+            if braced {
+                expressions.push(ExpressionKind::Error(Error::UnexpectedToken{
+                    expected : TokenType::RBRACE,
+                    unexpected : token.clone()
+                }).spanned(token.span));
+            }
+
+            return ExpressionKind::Block{
                 expressions
-            }.expr()
+            }.expr(span(tokens, start, current_index))
         }
 
         if let TokenType::RBRACE = token.r#type  {
             consume_token(tokens, current_index)?;
-            return Expression::Block{
-                expressions
-            }.expr()
+
+            if braced {
+                return ExpressionKind::Block{
+                    expressions
+                }.expr(span(tokens, start, current_index))
+            }
+
+            //a closing brace on the top level does not close anything
+            expressions.push(ExpressionKind::Error(Error::UnexpectedToken{
+                expected : TokenType::EOF,
+                unexpected : token.clone()
+            }).spanned(token.span));
+            continue;
         }
-    
-        let expression = expr(&tokens, current_index)?;
-
-
-        match expression.clone() {
-            Expression::StructDeclaration { name, r#type } => { match_optional_token(tokens, current_index, TokenType::SEMICOLON)? },
-            Expression::Block { expressions }  => { match_optional_token(&tokens, current_index, TokenType::SEMICOLON)?; },
-            Expression::If { condition, if_block, else_if_blocks, else_block }  => { match_optional_token(&tokens, current_index, TokenType::SEMICOLON)?; },
-            Expression::While { condition, block } => { match_optional_token(&tokens, current_index, TokenType::SEMICOLON)?; },
-            Expression::For { condition, block } => { match_optional_token(&tokens, current_index, TokenType::SEMICOLON)?; }, 
-            Expression::Fn { name, arguments, body, result } => {
-                if let Expression::Block { expressions } = *body {
-                    match_optional_token(&tokens, current_index, TokenType::SEMICOLON)?;
-                }
-            },
-            Expression::Overload { operation, arguments, result, body } => {
-                if let Expression::Block { expressions } = *body {
-                    match_optional_token(&tokens, current_index, TokenType::SEMICOLON)?;
-                }
+
+        let docs = doc_comments(tokens, current_index);
+
+        let statement_start = *current_index;
+
+        let expression = match body(tokens, current_index) {
+            Ok(expression) => attach_docs(expression, docs),
+            Err(error) => {
+                synchronize(tokens, current_index, statement_start);
+                expressions.push(ExpressionKind::Error(error).spanned(span(tokens, statement_start, current_index)));
+                continue;
             }
-            _ => { match_token(&tokens, current_index, TokenType::SEMICOLON)?; }
         };
-    
 
-        expressions.push(expression)
+        let terminated = terminate_statement(&expression, tokens, current_index);
+        expressions.push(expression);
+
+        //a missing semicolon does not make the statement itself wrong, so we keep both
+        if let Err(error) = terminated {
+            let error_span = tokens[*current_index].span;
+            synchronize(tokens, current_index, statement_start);
+            expressions.push(ExpressionKind::Error(error).spanned(error_span));
+        }
     }
 
 
-    Expression::Block{
+    ExpressionKind::Block{
         expressions
-    }.expr()
+    }.expr(span(tokens, start, current_index))
  
 }
 
@@ -1179,14 +2575,157 @@ fn match_optional_token(tokens : &Vec<Token>, current_index : &mut usize, option
     Ok(())
 }
 
-pub fn parse(tokens : Vec<Token>) -> Result<Vec<Expression>, Error> {
+//parses as much as possible, the tree contains error nodes wherever something could not be parsed and
+//the errors are returned alongside it (this is what tooling like editors would use)
+pub fn parse_partial(tokens : Vec<Token>) -> (Vec<Expression>, Vec<Error>) {
 
-    let index = &mut 0;
+    let tokens = strip_misplaced_docs(tokens);
 
+    let index = &mut 0;
 
-    if let Expression::Block { expressions } = block(&tokens, index)?{
-        return Ok(expressions)
+    let ast = match block(&tokens, index, false) {
+        Ok(Expression{ kind : ExpressionKind::Block { expressions }, .. }) => expressions,
+        Ok(other) => vec![other],
+        Err(error) => vec![ExpressionKind::Error(error).spanned(span(&tokens, 0, index))]
     };
 
-    Err(Error::Nil)
+    let mut errors = Vec::new();
+    for expression in ast.iter(){
+        expression.collect_errors(&mut errors);
+    }
+
+    (ast, errors)
+}
+
+pub fn parse(tokens : Vec<Token>) -> Result<Vec<Expression>, Vec<Error>> {
+
+    let (ast, errors) = parse_partial(tokens);
+
+    if errors.is_empty() {
+        Ok(ast)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::*;
+
+    fn parsed(text : &'static str) -> Vec<Expression>{
+        parse(lex(text).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn for_in_loops(){
+        let ast = parsed("for x in xs { x; }");
+
+        match &ast[0].kind {
+            ExpressionKind::For{ binding, range : ForRange::In(iterable), .. } => {
+                assert_eq!(binding.name.r#type, TokenType::ID("x".to_string()));
+                assert!(matches!(iterable.kind, ExpressionKind::LiteralID(_)));
+            },
+            other => panic!("expected a for loop, found {:?}", other)
+        }
+    }
+
+    #[test]
+    fn numeric_for_loops(){
+        let ast = parsed("for i = 0 to 10 step 2 { } for i = 0 to 10 { }");
+
+        assert!(matches!(&ast[0].kind, ExpressionKind::For{ range : ForRange::To{ step : Some(_), .. }, .. }));
+        assert!(matches!(&ast[1].kind, ExpressionKind::For{ range : ForRange::To{ step : None, .. }, .. }));
+    }
+
+    #[test]
+    fn for_loops_need_in_or_a_range(){
+        assert!(parse(lex("for x 5 { }").unwrap()).is_err());
+        assert!(parse(lex("for i = 0 { }").unwrap()).is_err());
+    }
+
+    #[test]
+    fn assignments_are_right_associative(){
+        let ast = parsed("a = b += 1;");
+
+        match &ast[0].kind {
+            ExpressionKind::Assign{ target, value } => {
+                assert!(matches!(target.kind, ExpressionKind::LiteralID(_)));
+                assert!(matches!(&value.kind, ExpressionKind::Binary{ operator, .. } if operator.r#type == TokenType::PLUSEQ));
+            },
+            other => panic!("expected an assignment, found {:?}", other)
+        }
+    }
+
+    //the start and end offsets of the source an expression was parsed from
+    fn offsets(expression : &Expression) -> (u32, u32){
+        (expression.span.start.offset, expression.span.end.offset)
+    }
+
+    #[test]
+    fn expressions_span_their_source(){
+        let ast = parsed("let x = 1 + 23;\nprint(x);");
+
+        assert_eq!(offsets(&ast[0]), (0, 14));
+        match &ast[0].kind {
+            ExpressionKind::Declaration{ value, .. } => assert_eq!(offsets(value), (8, 14)),
+            other => panic!("expected a declaration, found {:?}", other)
+        }
+
+        assert_eq!(offsets(&ast[1]), (16, 24));
+        assert_eq!((ast[1].span.start.line, ast[1].span.start.column), (1, 0));
+    }
+
+    #[test]
+    fn tokens_span_their_source(){
+        let tokens = lex("let name = \"ab\";").unwrap();
+
+        let spans : Vec<(u32, u32)> = tokens.iter().map(|token| (token.span.start.offset, token.span.end.offset)).collect();
+        assert_eq!(&spans[..5], &[(0, 3), (4, 8), (9, 10), (11, 15), (15, 16)]);
+    }
+
+    #[test]
+    fn every_parse_error_is_reported(){
+        let errors = parse(lex("let = 1; let y = ; print(2);").unwrap()).unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn parsing_continues_after_an_error(){
+        let (ast, errors) = parse_partial(lex("let = 1; print(2); let y = ; let z = 3;").unwrap());
+
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(ast[0].kind, ExpressionKind::Error(_)));
+        assert!(matches!(ast[1].kind, ExpressionKind::FunctionCall{ .. }));
+        assert!(matches!(ast[2].kind, ExpressionKind::Error(_)));
+        assert!(matches!(ast.last().unwrap().kind, ExpressionKind::Declaration{ .. }));
+    }
+
+    #[test]
+    fn doc_comments_belong_to_the_declaration_after_them(){
+        let ast = parsed("/// one\n/// two\nfn f() { }");
+
+        match &ast[0].kind {
+            ExpressionKind::Fn{ docs, .. } => assert_eq!(docs.len(), 2),
+            other => panic!("expected a function, found {:?}", other)
+        }
+    }
+
+    fn hash_of(number : &Number) -> u64{
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        number.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn numbers_are_equal_by_value(){
+        let (hex, decimal, exponent) = (Number::parse("0x1F").unwrap(), Number::parse("31").unwrap(), Number::parse("3.1e1").unwrap());
+
+        assert_eq!(hex, decimal);
+        assert_eq!(decimal, exponent);
+        assert_eq!(hash_of(&hex), hash_of(&decimal));
+        assert_ne!(decimal, Number::parse("31.5").unwrap());
+    }
 }
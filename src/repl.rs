@@ -0,0 +1,210 @@
+use crate::*;
+
+use std::cell::RefCell;
+use std::io::{BufRead, IsTerminal, Write};
+use std::rc::Rc;
+
+const HELP : &str = "enter statements to run them, the value and type of the last one is printed
+
+commands:
+    :type <expr>      prints the type of the expression without running it
+    :ast <expr>       prints the syntax tree of the expression
+    :tokens <expr>    prints the tokens of the expression
+    :reset            forgets everything that was declared
+    :help             prints this message
+    :quit             exits the repl (so does ctrl-d)";
+
+//everything declared in the repl lives on between inputs
+struct Session{
+    sources : SourceMap,
+    types : TypeEnvironment,
+    environment : Rc<RefCell<Environment>>,
+    colored : bool
+}
+
+impl Session{
+    fn new() -> Self{
+        Self{
+            sources : SourceMap::new(),
+            types : TypeEnvironment::global(),
+            environment : Rc::new(RefCell::new(Environment::global())),
+            colored : std::io::stderr().is_terminal() && std::env::var("NO_COLOR").is_err()
+        }
+    }
+
+    fn reset(&mut self){
+        self.types = TypeEnvironment::global();
+        //the functions declared so far hold on to the old scope, clearing it lets it go
+        self.environment.borrow_mut().clear();
+        self.environment = Rc::new(RefCell::new(Environment::global()));
+    }
+
+    fn report(&self, errors : Vec<Error>){
+        for error in errors{
+            eprint!("{}", error.diagnostic().render_in(&self.sources, self.colored));
+        }
+    }
+
+    //every input is its own file, so errors point at the right line of the right input
+    fn tokens(&mut self, input : &str) -> Result<Vec<Token>, Vec<Error>>{
+        let name = format!("<repl:{}>", self.sources.files().len() + 1);
+        let file = self.sources.add(name, input);
+
+        self.sources.get(file).unwrap().lex().map_err(|errors| errors.into_iter().map(Error::from).collect())
+    }
+
+    fn ast(&mut self, input : &str) -> Result<Vec<Expression>, Vec<Error>>{
+        parse(self.tokens(&terminated(input))?)
+    }
+
+    fn execute(&mut self, input : &str) -> Result<(), Vec<Error>>{
+        let ast = self.ast(input)?;
+
+        //a failed check or run should not leave half of the declarations behind. the input runs in a scope
+        //of its own whose declarations, just like the types, are only kept once it ran, so the types always
+        //describe what the environment holds. changes to what was declared before stay, like in any repl
+        let mut types = self.types.clone();
+        let r#type = check_expressions(&ast, &mut types).map_err(|error| vec![error])?;
+
+        let scope = Rc::new(RefCell::new(Environment::enclose(self.environment.clone())));
+        let value = evaluate(ast, scope.clone()).map_err(|error| vec![error])?;
+
+        scope.borrow_mut().move_into(&mut self.environment.borrow_mut());
+        self.types = types;
+
+        //statements like let evaluate to nil, printing that every time would only be noise
+        match value {
+            Value::Nil if r#type == Type::NullType => (),
+            //quoted, so "1" and 1 can be told apart
+            Value::Str(string) => println!("{:?} : {}", string, r#type),
+            value => println!("{} : {}", value, r#type)
+        }
+
+        Ok(())
+    }
+
+    fn command(&mut self, command : &str, argument : &str) -> Result<(), Vec<Error>>{
+        match command {
+            ":type" => {
+                let ast = self.ast(argument)?;
+
+                //only looking at the type should not declare anything
+                let mut types = self.types.clone();
+                let r#type = check_expressions(&ast, &mut types).map_err(|error| vec![error])?;
+                println!("{}", r#type);
+            },
+            ":ast" => println!("{:#?}", self.ast(argument)?),
+            ":tokens" => {
+                for token in self.tokens(argument)?{
+                    if token.r#type == TokenType::EOF {
+                        continue;
+                    }
+                    println!("{}\t{:?}", token.span.start.column + 1, token.r#type);
+                }
+            },
+            ":reset" => self.reset(),
+            ":help" => println!("{}", HELP),
+            _ => eprintln!("error: unknown command `{}`, see :help", command)
+        }
+
+        Ok(())
+    }
+}
+
+//the last statement of an input does not need its semicolon, so `1 + 2` can be typed as is. it goes right
+//after the last token so a comment at the end does not swallow it, statements ending in a block take an
+//optional one so `if x { }` and `let o = { a : 1 }` both work
+fn terminated(input : &str) -> String{
+    let last = match lex(input) {
+        Ok(tokens) => tokens.into_iter().rev().find(|x| x.r#type != TokenType::EOF),
+        Err(_) => None
+    };
+
+    match last {
+        Some(last) if !matches!(last.r#type, TokenType::SEMICOLON | TokenType::DOC(_)) => {
+            let end = last.span.end.offset as usize;
+            format!("{};{}", &input[..end], &input[end..])
+        },
+        _ => input.to_string()
+    }
+}
+
+//an input goes on over several lines while brackets are still open or a string or comment is not closed
+fn is_incomplete(input : &str) -> bool{
+    match lex(input) {
+        Ok(tokens) => {
+            let mut depth = 0;
+            for token in tokens{
+                match token.r#type {
+                    TokenType::LBRACE | TokenType::LPAREN | TokenType::LBRACK => depth += 1,
+                    TokenType::RBRACE | TokenType::RPAREN | TokenType::RBRACK => depth -= 1,
+                    _ => ()
+                }
+            }
+            depth > 0
+        },
+        Err(errors) => errors.iter().any(|error| {
+            matches!(error.kind, LexErrorKind::UnterminatedString | LexErrorKind::UnterminatedComment)
+        })
+    }
+}
+
+pub fn repl(){
+    let mut session = Session::new();
+
+    println!("bifrost repl, :help for help");
+
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        let mut input = String::new();
+
+        //keep reading until the input is complete, an empty line sends it anyway
+        loop {
+            print!("{}", if input.is_empty() { "> " } else { "| " });
+            let _ = std::io::stdout().flush();
+
+            let line = match lines.next() {
+                Some(Ok(line)) => line,
+                _ => {
+                    println!();
+                    return
+                }
+            };
+
+            if !input.is_empty() && line.trim().is_empty() {
+                break;
+            }
+
+            input += &line;
+            input += "\n";
+
+            if !is_incomplete(&input) {
+                break;
+            }
+        }
+
+        let trimmed = input.trim();
+
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let result = if trimmed.starts_with(':') {
+            let (command, argument) = trimmed.split_once(char::is_whitespace).unwrap_or((trimmed, ""));
+
+            if command == ":quit" {
+                return
+            }
+
+            session.command(command, argument)
+        } else {
+            session.execute(&input)
+        };
+
+        if let Err(errors) = result {
+            session.report(errors);
+        }
+    }
+}
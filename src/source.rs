@@ -0,0 +1,109 @@
+use crate::*;
+
+use std::path::{Path, PathBuf};
+
+//identifies a file inside of a SourceMap, spans carry it so errors know which file they are about
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FileId(pub u32);
+
+#[derive(Clone, Debug)]
+pub struct SourceFile{
+    pub id : FileId,
+    pub path : PathBuf,
+    pub text : String
+}
+
+impl SourceFile{
+    pub fn new(id : FileId, path : impl Into<PathBuf>, text : impl Into<String>) -> Self{
+        Self{
+            id,
+            path : path.into(),
+            text : text.into()
+        }
+    }
+
+    //the path the way it is shown in diagnostics
+    pub fn name(&self) -> String{
+        self.path.display().to_string()
+    }
+
+    //lexes the text of the file, every span (also the ones of errors) points into this file
+    pub fn lex(&self) -> Result<Vec<Token>, Vec<LexError>>{
+        match lex(&self.text) {
+            Ok(mut tokens) => {
+                for token in tokens.iter_mut(){
+                    token.span.file = self.id;
+                }
+                Ok(tokens)
+            },
+            Err(mut errors) => {
+                for error in errors.iter_mut(){
+                    error.span.file = self.id;
+                }
+                Err(errors)
+            }
+        }
+    }
+}
+
+//owns the text of every file of a program, so that tokens and errors only need to keep a FileId
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap{
+    files : Vec<SourceFile>
+}
+
+impl SourceMap{
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    pub fn add(&mut self, path : impl Into<PathBuf>, text : impl Into<String>) -> FileId{
+        let id = FileId(self.files.len() as u32);
+        self.files.push(SourceFile::new(id, path, text));
+        id
+    }
+
+    //reads the file from disk and adds it
+    pub fn load(&mut self, path : impl AsRef<Path>) -> std::io::Result<FileId>{
+        let text = std::fs::read_to_string(path.as_ref())?;
+        Ok(self.add(path.as_ref(), text))
+    }
+
+    pub fn get(&self, id : FileId) -> Option<&SourceFile>{
+        self.files.get(id.0 as usize)
+    }
+
+    pub fn files(&self) -> &[SourceFile]{
+        &self.files
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::*;
+
+    #[test]
+    fn tokens_and_errors_know_their_file(){
+        let mut sources = SourceMap::new();
+        let first = sources.add("first.bf", "let a = 1;");
+        let second = sources.add("second.bf", String::from("let b = \"2;"));
+
+        assert_eq!((first, second), (FileId(0), FileId(1)));
+        assert!(sources.get(first).unwrap().lex().unwrap().iter().all(|token| token.span.file == first));
+        assert!(sources.get(second).unwrap().lex().unwrap_err().iter().all(|error| error.span.file == second));
+    }
+
+    #[test]
+    fn diagnostics_are_rendered_against_their_file(){
+        let mut sources = SourceMap::new();
+        sources.add("first.bf", "let a = 1;");
+        let second = sources.add("second.bf", "let b : num = \"b\";");
+
+        let tokens = sources.get(second).unwrap().lex().unwrap();
+        let error = type_check(parse(tokens).unwrap()).unwrap_err();
+        let rendered = error.diagnostic().render_in(&sources, false);
+
+        assert!(rendered.contains("--> second.bf:1:15"), "{}", rendered);
+        assert!(rendered.contains("let b : num = \"b\";"), "{}", rendered);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Expression, Error, Type, Token, TokenType};
+use crate::{Expression, ExpressionKind, Error, ForRange, ObjectEntry, Payload, Pattern, PatternKind, PatternPayload, Span, Token, Type, TokenType, TypedName, TypeMismatch};
 
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -12,25 +12,49 @@ pub struct TypeEnvironment {
 
     //this is where stuff starting with type something goes to
     types : HashMap<String, Type>,
+    //the parameters of generic structs and types, Pair<A, B> is stored as Pair -> [A, B]
+    parameters : HashMap<String, Vec<String>>,
     //consts cannot be changed
+    constants : Vec<String>,
+    //names whose type inside this scope is narrower than what they were declared as, because a
+    //condition (x != nil, x is num, ...) told us so
+    narrowed : Vec<String>,
 
     enclosing : Option<Rc<RefCell<TypeEnvironment>>>,
     //this shows all possible mappings inside the current environment, because yes, even
     //overloadings are not global,
     //we always have this : (Operation, Type, Type) -> Type, that shows the operation that takes
     //type x type -> type
-    operations : HashMap<(TokenType, Type, Type), Type>
+    //unary operations (and their overloads) use NullType as their second type
+    operations : HashMap<(TokenType, Type, Type), Type>,
+
+    //the functions of impl blocks by (struct, function), the bool says whether it takes self (is a method)
+    methods : HashMap<(String, String), (Type, bool)>,
+
+    //the type the function we are currently inside of returns, None means we are not in a function
+    returns : Option<Type>,
+
+    //what every Inferred type has been worked out to be, shared by all environments of a program
+    unknowns : Rc<RefCell<Vec<Unknown>>>
+}
+
+//a type that is inferred from how it is used, origin is the name (or function) it belongs to
+#[derive(Clone, Debug)]
+struct Unknown{
+    bound : Option<Type>,
+    origin : Span
 }
 
 impl TypeEnvironment{
     //returns a new environemnt that is enclosed in the old one (is "nested" inside)
-    pub fn enclose(enclosing : TypeEnvironment) -> TypeEnvironment{  
-        
+    pub fn enclose(enclosing : TypeEnvironment) -> TypeEnvironment{
+
         Self{
+            unknowns : enclosing.unknowns.clone(),
             enclosing : Some(Rc::new(RefCell::new(enclosing))),
             ..Default::default()
-        } 
-    } 
+        }
+    }
 
     pub fn new() -> Self{
         return Self{
@@ -38,13 +62,54 @@ impl TypeEnvironment{
         }
     }
 
+    //the environment every program is checked in, knows about the builtin operations and functions
+    pub fn global() -> Self{
+        let mut env = Self::new();
+
+        for operation in [
+            TokenType::PLUS, TokenType::MINUS, TokenType::STAR, TokenType::SLASH, TokenType::PERCENT, TokenType::STARSTAR,
+            TokenType::AMP, TokenType::BAR, TokenType::CARET, TokenType::SHL, TokenType::SHR
+        ]{
+            env.operations.insert((operation, Type::NumType, Type::NumType), Type::NumType);
+        }
+
+        for operation in [TokenType::GE, TokenType::GEQ, TokenType::LE, TokenType::LEQ]{
+            env.operations.insert((operation, Type::NumType, Type::NumType), Type::BoolType);
+        }
+
+        for operation in [TokenType::AND, TokenType::OR, TokenType::XOR]{
+            env.operations.insert((operation, Type::BoolType, Type::BoolType), Type::BoolType);
+        }
+
+        env.operations.insert((TokenType::PLUS, Type::StrType, Type::StrType), Type::StrType);
+        env.operations.insert((TokenType::BANG, Type::BoolType, Type::NullType), Type::BoolType);
+        env.operations.insert((TokenType::MINUS, Type::NumType, Type::NullType), Type::NumType);
+
+        //print takes any number of arguments so it cannot be expressed as a function type
+        env.values.insert("print".to_string(), Type::AnyType);
+        env.values.insert("len".to_string(), Type::FunctionType{
+            arguments : vec![Type::AnyType],
+            returns : Box::new(Type::NumType)
+        });
+        env.values.insert("type".to_string(), Type::FunctionType{
+            arguments : vec![Type::AnyType],
+            returns : Box::new(Type::StrType)
+        });
+        env.values.insert("push".to_string(), Type::FunctionType{
+            arguments : vec![Type::ArrayType(Box::new(Type::AnyType)), Type::AnyType],
+            returns : Box::new(Type::NullType)
+        });
+
+        env
+    }
+
 
     pub fn get_type(&mut self, key : String) -> Option<Type>{
-       
+
         //check in the current environment before moving upwards
         if let Some(type_inside) = self.values.get(&key){
             return Some(type_inside.clone())
-        } 
+        }
 
         //then we check upwards (recursively)
         if let Some(enclosing) = &self.enclosing{
@@ -54,90 +119,2143 @@ impl TypeEnvironment{
 
         //if all this fails we return none, meaning the type does not exist in the codebase
         return None
-    } 
-   
+    }
 
-    pub fn assign_type(&mut self, key : String, assign_type : Type) -> Result<(), Error>{
 
-        //check if the value already exists and if yes we return (since we cannot set)
-        if let Some(_) = self.values.get(&key){
-            return Ok(());
-        }
+    //declaring a name again in the same scope shadows the old declaration
+    pub fn assign_type(&mut self, key : String, assign_type : Type) -> Result<(), Error>{
 
+        self.constants.retain(|x| *x != key);
+        self.narrowed.retain(|x| *x != key);
         self.values.insert(key, assign_type);
-         
+
+        Ok(())
+    }
+
+    pub fn assign_constant(&mut self, key : String, assign_type : Type) -> Result<(), Error>{
+        self.assign_type(key.clone(), assign_type)?;
+        self.constants.push(key);
+
         Ok(())
     }
 
-    //checks the type provided against the keyed type inside the environment
-    pub fn check_type(&mut self, key : String, check_type : Type) -> Result<bool, Error> {
+    //narrows a name for this scope only, the declaration it comes from is left untouched
+    pub fn narrow(&mut self, key : String, narrowed_type : Type){
+        self.values.insert(key.clone(), narrowed_type);
+        self.narrowed.push(key);
+    }
+
+    //the type a name was declared with, skipping over every narrowing of it
+    pub fn get_declared_value_type(&mut self, key : String) -> Option<Type>{
+        if !self.narrowed.contains(&key) {
+            if let Some(type_inside) = self.values.get(&key){
+                return Some(type_inside.clone())
+            }
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            return enclosing.borrow_mut().get_declared_value_type(key)
+        }
+
+        None
+    }
+
+    //after an assignment a narrowed name can also be what was assigned, up to the scope that declared it
+    pub fn widen(&mut self, key : String, assigned_type : Type){
+        if self.narrowed.contains(&key) {
+            let narrowed_type = self.values.get(&key).cloned().unwrap_or(Type::AnyType);
+            self.values.insert(key.clone(), union_of(vec![narrowed_type, assigned_type.clone()]));
+        } else if self.values.contains_key(&key) {
+            return
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            enclosing.borrow_mut().widen(key, assigned_type)
+        }
+    }
+
+    //only the scope that declared a name knows whether it is constant
+    pub fn is_constant(&mut self, key : String) -> bool{
+        if self.values.contains_key(&key) && !self.narrowed.contains(&key) {
+            return self.constants.contains(&key)
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            return enclosing.borrow_mut().is_constant(key)
+        }
+
+        false
+    }
+
+    //checks the type provided against the keyed type inside the environment, the span is where the name
+    //was used so a missing name is reported there
+    pub fn check_type(&mut self, key : String, check_type : Type, span : Span) -> Result<bool, Error> {
         if let Some(type_inside) = self.values.get(&key)  {
             return Ok(*type_inside == check_type)
         }
-        return Err(Error::TypeNotFound)
+        return Err(Error::TypeNotFound{
+            name : key,
+            span
+        })
+    }
+
+    pub fn declare_type(&mut self, key : String, declared_type : Type){
+        self.types.insert(key, declared_type);
+    }
+
+    //looks up what a custom type (type x = ... or struct x {...}) stands for
+    pub fn get_declared_type(&mut self, key : String) -> Option<Type>{
+        if let Some(type_inside) = self.types.get(&key){
+            return Some(type_inside.clone())
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            return enclosing.borrow_mut().get_declared_type(key)
+        }
+
+        None
+    }
+
+    pub fn declare_parameters(&mut self, key : String, parameters : Vec<String>){
+        self.parameters.insert(key, parameters);
+    }
+
+    //the parameters of a generic struct or type, nothing for everything else
+    pub fn get_parameters(&mut self, key : String) -> Vec<String>{
+        if let Some(parameters) = self.parameters.get(&key){
+            return parameters.clone()
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            return enclosing.borrow_mut().get_parameters(key)
+        }
+
+        Vec::new()
+    }
+
+    //fills the parameters of the generic struct or type called key into a type, the ones without an
+    //argument (like the raw Pair instead of Pair<num, string>) become any
+    pub fn apply(&mut self, key : String, r#type : &Type, arguments : &[Type]) -> Type{
+        let parameters = self.get_parameters(key);
+
+        r#type.replace(&|x| match x {
+            Type::Param(name) => parameters.iter().position(|x| x == name)
+                .map(|index| arguments.get(index).cloned().unwrap_or(Type::AnyType)),
+            _ => None
+        })
+    }
+
+    //what a struct or type with the given arguments was declared as
+    pub fn instantiate(&mut self, key : String, arguments : &[Type]) -> Option<Type>{
+        let declared = self.get_declared_type(key.clone())?;
+        Some(self.apply(key, &declared, arguments))
+    }
+
+    //a new type that is worked out later
+    pub fn unknown(&mut self, origin : Span) -> Type{
+        let mut unknowns = self.unknowns.borrow_mut();
+        unknowns.push(Unknown{
+            bound : None,
+            origin
+        });
+
+        Type::Inferred(unknowns.len() - 1)
+    }
+
+    pub fn origin_of(&self, id : usize) -> Span{
+        self.unknowns.borrow()[id].origin
+    }
+
+    //replaces everything inside of the type that has been worked out by what it was worked out to be
+    pub fn fill_in(&self, r#type : &Type) -> Type{
+        r#type.replace(&|x| match x {
+            Type::Inferred(id) => {
+                let bound = self.unknowns.borrow()[*id].bound.clone();
+                bound.map(|bound| self.fill_in(&bound))
+            },
+            _ => None
+        })
+    }
+
+    fn bind(&mut self, id : usize, bound : &Type){
+        let bound = self.fill_in(bound);
+
+        //something that contains itself cannot be written down, so it could be anything
+        let contains = std::cell::Cell::new(false);
+        bound.replace(&|x| {
+            if *x == Type::Inferred(id) {
+                contains.set(true);
+            }
+            None
+        });
+
+        self.unknowns.borrow_mut()[id].bound = Some(if contains.get() { Type::AnyType } else { bound });
+    }
+
+    //results that are inferred from several returns become all of them
+    pub fn widen_unknown(&mut self, id : usize, found : &Type){
+        let widened = match self.unknowns.borrow()[id].bound.clone() {
+            Some(bound) => union_of(vec![bound, found.clone()]),
+            None => found.clone()
+        };
+
+        self.bind(id, &widened);
+    }
+
+    //an unknown that nothing worked out could be anything from now on
+    pub fn give_up(&mut self, id : usize){
+        let mut unknowns = self.unknowns.borrow_mut();
+        if unknowns[id].bound.is_none() {
+            unknowns[id].bound = Some(Type::AnyType);
+        }
+    }
+
+    pub fn is_unbound(&self, r#type : &Type) -> bool{
+        match r#type {
+            Type::Inferred(id) => self.unknowns.borrow()[*id].bound.is_none(),
+            _ => false
+        }
+    }
+
+    pub fn declare_operation(&mut self, operation : TokenType, left : Type, right : Type, result : Type){
+        self.operations.insert((operation, left, right), result);
+    }
+
+    pub fn get_operation(&mut self, operation : TokenType, left : Type, right : Type) -> Option<Type>{
+        if let Some(result) = self.operations.get(&(operation.clone(), left.clone(), right.clone())){
+            return Some(result.clone())
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            return enclosing.borrow_mut().get_operation(operation, left, right)
+        }
+
+        None
+    }
+
+    //every operation with the operator, used to work out what an unknown operand has to be
+    pub fn operations_of(&self, operation : &TokenType) -> Vec<((Type, Type), Type)>{
+        let mut found : Vec<((Type, Type), Type)> = self.operations.iter()
+            .filter(|((x, _, _), _)| x == operation)
+            .map(|((_, left, right), result)| ((left.clone(), right.clone()), result.clone()))
+            .collect();
+
+        if let Some(enclosing) = &self.enclosing{
+            for operation in enclosing.borrow().operations_of(operation){
+                if !found.iter().any(|x| x.0 == operation.0) {
+                    found.push(operation);
+                }
+            }
+        }
+
+        found
+    }
+
+    pub fn declare_method(&mut self, struct_name : String, name : String, method_type : Type, takes_self : bool){
+        self.methods.insert((struct_name, name), (method_type, takes_self));
+    }
+
+    pub fn get_method(&mut self, struct_name : String, name : String) -> Option<(Type, bool)>{
+        if let Some(method) = self.methods.get(&(struct_name.clone(), name.clone())){
+            return Some(method.clone())
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            return enclosing.borrow_mut().get_method(struct_name, name)
+        }
+
+        None
+    }
+
+    pub fn get_return_type(&mut self) -> Option<Type>{
+        if let Some(returns) = &self.returns{
+            return Some(returns.clone())
+        }
+
+        if let Some(enclosing) = &self.enclosing{
+            return enclosing.borrow_mut().get_return_type()
+        }
+
+        None
+    }
+
+    //replaces custom types by what they were declared as, until we hit something concrete, unknown
+    //types stay what they are (validate is responsible for reporting them)
+    pub fn resolve(&mut self, unresolved : &Type) -> Type{
+        match unresolved {
+            Type::CustomType(name) | Type::Applied { name, .. } => {
+                let arguments = match unresolved {
+                    Type::Applied { arguments, .. } => arguments.clone(),
+                    _ => Vec::new()
+                };
+
+                match self.get_declared_type(name.to_string()) {
+                    //structs and enums stay named, everything else (type x = ...) is just an alias
+                    Some(Type::Struct { .. } | Type::Enum { .. }) | None => unresolved.clone(),
+                    Some(declared) => {
+                        let applied = self.apply(name.to_string(), &declared, &arguments);
+                        self.resolve(&applied)
+                    }
+                }
+            },
+            //as long as nothing is known about it, it could be anything
+            Type::Inferred(id) => match self.fill_in(&Type::Inferred(*id)) {
+                Type::Inferred(_) => Type::AnyType,
+                known => self.resolve(&known)
+            },
+            _ => unresolved.clone()
+        }
+    }
+
+    //whether resolving the type can lead back to the alias key without passing through something that is
+    //not an alias (a struct, an array, ...), those are the aliases resolve would loop on forever
+    pub fn refers_to(&mut self, key : &str, r#type : &Type, visited : &mut Vec<String>) -> bool{
+        match r#type {
+            Type::CustomType(name) | Type::Applied { name, .. } => {
+                if name == key {
+                    return true
+                }
+                if visited.contains(name) {
+                    return false
+                }
+                visited.push(name.to_string());
+
+                match self.get_declared_type(name.to_string()) {
+                    Some(Type::Struct { .. } | Type::Enum { .. }) | None => false,
+                    Some(declared) => self.refers_to(key, &declared, visited)
+                }
+            },
+            Type::UnionType(options) => options.iter().any(|x| self.refers_to(key, x, visited)),
+            _ => false
+        }
+    }
+
+    //makes sure every custom type inside of a type annotation has been declared, the span is where
+    //the annotation was used
+    pub fn validate(&mut self, check_type : &Type, span : Span) -> Result<(), Error>{
+        match check_type {
+            Type::CustomType(name) if self.get_declared_type(name.to_string()).is_none() => {
+                return Err(Error::TypeNotFound{
+                    name : name.to_string(),
+                    span
+                })
+            },
+            Type::Applied { name, arguments } => {
+                self.validate(&Type::CustomType(name.to_string()), span)?;
+
+                let parameters = self.get_parameters(name.to_string());
+                if parameters.len() != arguments.len(){
+                    return Err(Error::TypeArgumentMismatch{
+                        name : name.to_string(),
+                        expected : parameters.len(),
+                        found : arguments.len(),
+                        span
+                    })
+                }
+
+                for argument in arguments{
+                    self.validate(argument, span)?;
+                }
+            },
+            Type::ArrayType(inner) => self.validate(inner, span)?,
+            Type::UnionType(options) => {
+                for option in options{
+                    self.validate(option, span)?;
+                }
+            },
+            Type::ObjectType { types, .. } | Type::Struct { types, .. } => {
+                for field_type in types{
+                    self.validate(field_type, span)?;
+                }
+            },
+            Type::Enum { payloads, .. } => {
+                for payload in payloads{
+                    match payload {
+                        Payload::Unit => (),
+                        Payload::Tuple(types) | Payload::Fields { types, .. } => {
+                            for payload_type in types{
+                                self.validate(payload_type, span)?;
+                            }
+                        }
+                    }
+                }
+            },
+            Type::FunctionType { arguments, returns } => {
+                for argument in arguments{
+                    self.validate(argument, span)?;
+                }
+                self.validate(returns, span)?;
+            },
+            _ => ()
+        }
+
+        Ok(())
+    }
+
+    //checks if values of the type can be put into a string, which works for everything but functions
+    pub fn is_stringifiable(&mut self, r#type : &Type) -> bool{
+        match self.resolve(r#type) {
+            Type::FunctionType { .. } => false,
+            Type::ArrayType(inner) => self.is_stringifiable(&inner),
+            Type::UnionType(types) | Type::ObjectType { types, .. } => types.iter().all(|x| self.is_stringifiable(x)),
+            _ => true
+        }
+    }
+
+    //checks if a value of type found can be used where expected is required
+    pub fn is_assignable(&mut self, expected : &Type, found : &Type) -> bool{
+        if expected == found {
+            return true
+        }
+
+        //what has not been worked out yet becomes what it is used as
+        match (self.fill_in(expected), self.fill_in(found)) {
+            (expected, found) if expected == found => return true,
+            (Type::Inferred(id), found) => {
+                self.bind(id, &found);
+                return true
+            },
+            (expected, Type::Inferred(id)) => {
+                self.bind(id, &expected);
+                return true
+            },
+            _ => ()
+        }
+
+        let expected = self.resolve(expected);
+        let found = self.resolve(found);
+
+        let assignable = match (&expected, &found) {
+            (Type::AnyType, _) | (_, Type::AnyType) => true,
+
+            //every option of the found union has to fit into the expected type
+            (_, Type::UnionType(found_options)) => {
+                let mut all = true;
+                for option in found_options{
+                    all = all && self.is_assignable(&expected, option);
+                }
+                all
+            },
+            (Type::UnionType(expected_options), _) => {
+                let mut any = false;
+                for option in expected_options{
+                    any = any || self.is_assignable(option, &found);
+                }
+                any
+            },
+
+            (Type::ArrayType(expected_inner), Type::ArrayType(found_inner)) => {
+                self.is_assignable(expected_inner, found_inner)
+            },
+
+            //a generic struct without arguments takes every version of it
+            (Type::Applied { name, arguments }, Type::Applied { name : found_name, arguments : found_arguments }) => {
+                let mut all = name == found_name && arguments.len() == found_arguments.len();
+                for (argument, found_argument) in arguments.iter().zip(found_arguments.iter()){
+                    all = all && self.is_assignable(argument, found_argument);
+                }
+                all
+            },
+            (Type::CustomType(name), Type::Applied { name : found_name, .. })
+                | (Type::Applied { name, .. }, Type::CustomType(found_name)) => name == found_name,
+
+            //objects are structural, the found object needs at least the expected fields
+            (Type::ObjectType { keys, types }, Type::ObjectType { keys : found_keys, types : found_types }) => {
+                let mut all = true;
+                for (key, field_type) in keys.iter().zip(types.iter()){
+                    all = all && match found_keys.iter().position(|x| x == key) {
+                        Some(index) => self.is_assignable(field_type, &found_types[index]),
+                        None => false
+                    };
+                }
+                all
+            },
+
+            (Type::FunctionType { arguments, returns }, Type::FunctionType { arguments : found_arguments, returns : found_returns }) => {
+                let mut all = arguments.len() == found_arguments.len() && self.is_assignable(returns, found_returns);
+                for (argument, found_argument) in arguments.iter().zip(found_arguments.iter()){
+                    all = all && self.is_assignable(found_argument, argument);
+                }
+                all
+            },
+
+            _ => expected == found
+        };
+
+        assignable
+    }
+
+    //throws a type mismatch at span if found cannot be used as expected
+    pub fn expect(&mut self, expected : &Type, found : &Type, span : Span) -> Result<(), Error>{
+        if self.is_assignable(expected, found) {
+            return Ok(())
+        }
+
+        Err(Error::TypeMismatch(Box::new(TypeMismatch{
+            expected : self.fill_in(expected),
+            found : self.fill_in(found),
+            span,
+            expected_from : None,
+            inferred_from : None
+        })))
     }
 }
 
 impl Default for TypeEnvironment{
     fn default() -> Self {
-    
+
         Self{
             enclosing : None,
-            operations :HashMap::new(), 
+            operations :HashMap::new(),
+            methods : HashMap::new(),
             types : HashMap::new(),
-            values : HashMap::new()
+            parameters : HashMap::new(),
+            values : HashMap::new(),
+            constants : Vec::new(),
+            narrowed : Vec::new(),
+            returns : None,
+            unknowns : Rc::new(RefCell::new(Vec::new()))
+        }
+    }
+}
+
+//builds the smallest union out of a list of types, a single type stays itself
+pub fn union_of(options : Vec<Type>) -> Type{
+    let mut distinct : Vec<Type> = Vec::new();
+
+    for option in options{
+        let flattened = match option {
+            Type::UnionType(inner) => inner,
+            _ => vec![option]
+        };
+
+        for inner in flattened{
+            if !distinct.contains(&inner){
+                distinct.push(inner);
+            }
         }
     }
+
+    if distinct.contains(&Type::AnyType){
+        return Type::AnyType
+    }
+
+    match distinct.len() {
+        0 => Type::NullType,
+        1 => distinct.remove(0),
+        _ => Type::UnionType(distinct)
+    }
 }
 
 pub fn type_check(ast : Vec<Expression>) -> Result<(), Error>{
 
-    let global_env = TypeEnvironment::new();
+    let mut global_env = TypeEnvironment::global();
 
-    check_expression(ast, global_env)?;
+    check_expressions(&ast, &mut global_env)?;
     Ok(())
 }
 
 
 //this checks first of all if we have any kind of internal type errors, also when handling blocks
 //we need to check what kind of type is returned
-pub fn check_expression(ast : Vec<Expression>, enclosing : TypeEnvironment) -> Result<Type, Error>{
+pub fn check_expressions(ast : &[Expression], enclosing : &mut TypeEnvironment) -> Result<Type, Error>{
+
+    let mut last = Type::NullType;
 
     for expression in ast{
-        match expression {
-            Expression::TypeDeclaration { name, r#type } | Expression::StructDeclaration { name, r#type } => {
+        last = check_expression(expression, enclosing)?;
+    }
 
-            },
-            Expression::Declaration { name, value, constant } => {
+    Ok(last)
+}
 
-            },
-            Expression::Assign { target, value } => {
+//infers the type of a single expression, declarations change the environment they are checked in
+pub fn check_expression(expression : &Expression, enclosing : &mut TypeEnvironment) -> Result<Type, Error>{
 
-            },
+    let span = expression.span;
 
-            Expression::Overload { operation, arguments, result, body } => {
+    match &expression.kind {
+        ExpressionKind::LiteralNum(_) => Ok(Type::NumType),
+        ExpressionKind::LiteralStr(_) => Ok(Type::StrType),
+        ExpressionKind::LiteralBool(_) => Ok(Type::BoolType),
+        ExpressionKind::LiteralNil => Ok(Type::NullType),
+        ExpressionKind::Interpolated(parts) => {
+            for part in parts{
+                let part_type = check_expression(part, enclosing)?;
 
-            },
+                if !enclosing.is_stringifiable(&part_type) {
+                    return Err(Error::NotStringifiable{
+                        found : part_type.to_string(),
+                        span : part.span
+                    })
+                }
+            }
 
-            Expression::FunctionCall { function, arguments } => {
+            Ok(Type::StrType)
+        },
+        ExpressionKind::LiteralID(name) => {
+            let found = enclosing.get_type(name.to_string()).ok_or(Error::UndefinedVariable{
+                name : name.to_string(),
+                span
+            })?;
 
-            },
+            Ok(enclosing.fill_in(&found))
+        },
 
-            Expression::Binary { left, operator, right } => {
-            },
+        ExpressionKind::LiteralArray(elements) => {
+            let mut element_types = Vec::new();
+            for element in elements{
+                element_types.push(check_expression(element, enclosing)?);
+            }
 
-            Expression::Block { expressions } => {
-                let env = TypeEnvironment::enclose(enclosing.clone());
-            
-                let return_type = check_expression(expressions, env);     
-                
-                return return_type
+            //an empty array can be used as any array
+            if element_types.is_empty(){
+                return Ok(Type::ArrayType(Box::new(Type::AnyType)))
             }
-            _ => ()
-        }
-    } 
 
+            Ok(Type::ArrayType(Box::new(union_of(element_types))))
+        },
+
+        ExpressionKind::LiteralObject(entries) => {
+            let mut object_keys : Vec<String> = Vec::new();
+            let mut types : Vec<Type> = Vec::new();
+
+            //later fields replace earlier ones with the same key, just like when the object is built
+            let mut set = |key : String, field_type : Type| {
+                match object_keys.iter().position(|x| *x == key) {
+                    Some(index) => types[index] = field_type,
+                    None => {
+                        object_keys.push(key);
+                        types.push(field_type);
+                    }
+                }
+            };
+
+            //spreading something we know nothing about means we do not know the fields either
+            let mut known = true;
+
+            for entry in entries{
+                match entry {
+                    ObjectEntry::Field { key, value } => {
+                        let key = match &key.r#type {
+                            TokenType::ID(name) | TokenType::STR(name) => name.to_string(),
+                            _ => continue
+                        };
+                        set(key, check_expression(value, enclosing)?);
+                    },
+                    ObjectEntry::Spread { operator, value } => {
+                        let spread_type = check_expression(value, enclosing)?;
+
+                        let resolved = fields_of(&spread_type, enclosing);
+
+                        match resolved {
+                            Type::AnyType => known = false,
+                            Type::ObjectType { keys, types } | Type::Struct { keys, types } => {
+                                for (key, field_type) in keys.into_iter().zip(types){
+                                    set(key, field_type);
+                                }
+                            },
+                            _ => return Err(Error::InvalidOperands{
+                                operator : operator.clone(),
+                                operands : vec![spread_type.to_string()]
+                            })
+                        }
+                    }
+                }
+            }
+
+            if !known {
+                return Ok(Type::AnyType)
+            }
+
+            Ok(Type::ObjectType{
+                keys : object_keys,
+                types
+            })
+        },
+
+        ExpressionKind::Grp { inner } => check_expression(inner, enclosing),
+
+        ExpressionKind::TypeDeclaration { name, r#type, .. }
+            | ExpressionKind::StructDeclaration { name, r#type, .. }
+            | ExpressionKind::EnumDeclaration { name, r#type, .. } => {
+            let key = name.r#type.get_id_val().unwrap_or_default();
+
+            //structs and enums are declared before they are validated so they can reference themselves
+            let declared = match (&expression.kind, r#type) {
+                (ExpressionKind::StructDeclaration { .. }, Type::ObjectType { keys, types }) => Type::Struct{
+                    keys : keys.clone(),
+                    types : types.clone()
+                },
+                _ => r#type.clone()
+            };
+
+            let generics = match &expression.kind {
+                ExpressionKind::TypeDeclaration { generics, .. } | ExpressionKind::StructDeclaration { generics, .. } => generics.clone(),
+                _ => Vec::new()
+            };
+
+            enclosing.declare_parameters(key.clone(), generics);
+            enclosing.declare_type(key.clone(), declared);
+            enclosing.validate(r#type, span)?;
+
+            //type L = L | nil never gets to anything concrete
+            if let ExpressionKind::TypeDeclaration { .. } = expression.kind {
+                if enclosing.refers_to(&key, r#type, &mut Vec::new()) {
+                    return Err(Error::RecursiveAlias{
+                        name : key,
+                        span : name.span
+                    })
+                }
+            }
+
+            Ok(Type::NullType)
+        },
+
+        ExpressionKind::Declaration { name, value, constant } => {
+            let key = name.name.r#type.get_id_val().unwrap_or_default();
+            enclosing.validate(&name.r#type, name.name.span)?;
+
+            let value_type = check_expression(value, enclosing)?;
+
+            let declared_type = if name.annotated {
+                let checked = enclosing.expect(&name.r#type, &value_type, value.span);
+                noting_inferred(checked, value, enclosing).map_err(|error| match error {
+                    Error::TypeMismatch(mut mismatch) => {
+                        mismatch.expected_from = Some(name.name.span);
+                        Error::TypeMismatch(mismatch)
+                    },
+                    other => other
+                })?;
+
+                name.r#type.clone()
+            } else {
+                //names without an annotation are whatever their value is, nil and {} say nothing about
+                //what is assigned later though
+                let inferred = enclosing.unknown(name.name.span);
+                let value_type = match enclosing.fill_in(&value_type) {
+                    Type::NullType => Type::AnyType,
+                    Type::ObjectType { keys, .. } if keys.is_empty() => Type::AnyType,
+                    other => other
+                };
+                enclosing.is_assignable(&inferred, &value_type);
+
+                inferred
+            };
+
+            if *constant {
+                enclosing.assign_constant(key, declared_type)?;
+            } else {
+                enclosing.assign_type(key, declared_type)?;
+            }
+
+            Ok(Type::NullType)
+        },
+
+        ExpressionKind::Assign { target, value } => check_assign(target, value, enclosing),
+
+        ExpressionKind::Overload { operation, arguments, result, body } => {
+            let argument_types : Vec<Type> = arguments.iter().map(|x| x.r#type.clone()).collect();
+            for argument in arguments{
+                enclosing.validate(&argument.r#type, argument.name.span)?;
+            }
+            enclosing.validate(result, span)?;
+
+            match argument_types.as_slice() {
+                [single] => enclosing.declare_operation(operation.r#type.clone(), single.clone(), Type::NullType, result.clone()),
+                [left, right] => enclosing.declare_operation(operation.r#type.clone(), left.clone(), right.clone(), result.clone()),
+                _ => return Err(Error::ArityMismatch{
+                    expected : 2,
+                    found : argument_types.len(),
+                    span
+                })
+            }
+
+            check_function_body(arguments, result, body, enclosing)?;
+
+            Ok(Type::NullType)
+        },
+
+        ExpressionKind::Fn { name, generics, arguments, result, body, .. } => {
+            let (arguments, returns) = signature_of(arguments, result, span, enclosing);
+
+            let function_type = Type::FunctionType{
+                arguments : arguments.iter().map(|x| x.r#type.clone()).collect(),
+                returns : Box::new(returns.clone())
+            };
+            enclosing.validate(&function_type, span)?;
+
+            //named functions are declared before their body is checked so they can recurse
+            let name = name.as_ref().and_then(|x| x.r#type.get_id_val());
+            if let Some(name) = &name{
+                enclosing.assign_type(name.to_string(), function_type.clone())?;
+            }
+
+            let body_type = check_function_body(&arguments, &returns, body, &mut generic_scope(generics, enclosing))?;
+
+            let function_type = settle(&arguments, &returns, result, body, &body_type, enclosing);
+            if let Some(name) = name{
+                enclosing.assign_type(name, function_type.clone())?;
+            }
+
+            Ok(function_type)
+        },
+
+        ExpressionKind::FunctionCall { function, arguments } => {
+            let function_type = check_expression(function, enclosing)?;
+
+            check_call(&function_type, function.span, arguments, span, enclosing)
+        },
+
+        ExpressionKind::FieldAccess { target, field } => {
+            let target_type = check_expression(target, enclosing)?;
+            let name = field.r#type.get_id_val().unwrap_or_default();
 
-    
-    Ok(Type::NullType)
-} 
+            field_type(&target_type, &name, field.span, enclosing)
+        },
+
+        ExpressionKind::Index { target, index } => {
+            let target_type = check_expression(target, enclosing)?;
+            let index_type = check_expression(index, enclosing)?;
+
+            match (enclosing.resolve(&target_type), enclosing.resolve(&index_type)) {
+                (Type::AnyType, _) => Ok(Type::AnyType),
+                (Type::ArrayType(inner), Type::NumType | Type::AnyType) => Ok(*inner),
+                (Type::StrType, Type::NumType | Type::AnyType) => Ok(Type::StrType),
+                (Type::ObjectType { .. } | Type::CustomType(_) | Type::Applied { .. }, Type::StrType | Type::AnyType) => Ok(Type::AnyType),
+                _ => Err(Error::InvalidOperands{
+                    operator : TokenType::LBRACK.spanned(span),
+                    operands : vec![target_type.to_string(), index_type.to_string()]
+                })
+            }
+        },
+
+        //methods of the struct come first, otherwise it is a field that holds a function
+        ExpressionKind::MethodCall { target, method, arguments } => {
+            let target_type = check_expression(target, enclosing)?;
+            let name = method.r#type.get_id_val().unwrap_or_default();
+
+            if let Type::CustomType(struct_name) | Type::Applied { name : struct_name, .. } = enclosing.resolve(&target_type) {
+                match enclosing.get_method(struct_name.clone(), name.clone()) {
+                    Some((Type::FunctionType { arguments : expected, returns }, true)) => {
+                        //self is the target, the call supplies the rest
+                        let method_type = Type::FunctionType{
+                            arguments : expected[1..].to_vec(),
+                            returns
+                        };
+                        return check_call(&method_type, method.span, arguments, span, enclosing)
+                    },
+                    Some(_) => return Err(Error::NotAMethod{
+                        struct_name,
+                        function : name,
+                        span : method.span
+                    }),
+                    None => {
+                        return match field_type(&target_type, &name, method.span, enclosing) {
+                            Ok(field) => check_call(&field, method.span, arguments, span, enclosing),
+                            Err(_) => Err(Error::MethodNotFound{
+                                struct_name,
+                                method : name,
+                                span : method.span
+                            })
+                        }
+                    }
+                }
+            }
+
+            let method_type = field_type(&target_type, &name, method.span, enclosing)?;
+            check_call(&method_type, method.span, arguments, span, enclosing)
+        },
+
+        ExpressionKind::Associated { struct_name, function } => {
+            let key = struct_name.r#type.get_id_val().unwrap_or_default();
+            let name = function.r#type.get_id_val().unwrap_or_default();
+            let declared = nominal_of(struct_name, enclosing)?;
+
+            match (declared, enclosing.get_method(key.clone(), name.clone())) {
+                //variants come before the functions of impl blocks, without either variant_of reports it
+                (Type::Enum { variants, .. }, method) if variants.contains(&name) || method.is_none() => {
+                    let path = format!("{}::{}", key, name);
+
+                    //unit variants are values, tuple variants are functions that build the value
+                    match variant_of(struct_name, function, enclosing)? {
+                        Payload::Unit => Ok(Type::CustomType(key)),
+                        Payload::Tuple(types) => Ok(Type::FunctionType{
+                            arguments : types,
+                            returns : Box::new(Type::CustomType(key))
+                        }),
+                        payload => Err(Error::PayloadMismatch{
+                            usage : payload.usage(&path),
+                            variant : path,
+                            span : function.span
+                        })
+                    }
+                },
+                (_, Some((function_type, _))) => Ok(function_type),
+                (_, None) => Err(Error::MethodNotFound{
+                    struct_name : key,
+                    method : name,
+                    span : function.span
+                })
+            }
+        },
+
+        ExpressionKind::Impl { struct_name, functions } => {
+            let key = struct_name.r#type.get_id_val().unwrap_or_default();
+            nominal_of(struct_name, enclosing)?;
+
+            //every signature is known before the bodies are checked so the functions can use each other, what
+            //is not annotated is worked out by the bodies (or by the other functions using it)
+            let mut signatures = Vec::new();
+            for function in functions{
+                if let ExpressionKind::Fn { name : Some(name), arguments, result, .. } = &function.kind {
+                    let (arguments, returns) = signature_of(arguments, result, function.span, enclosing);
+                    let function_type = Type::FunctionType{
+                        arguments : arguments.iter().map(|x| x.r#type.clone()).collect(),
+                        returns : Box::new(returns.clone())
+                    };
+                    enclosing.validate(&function_type, function.span)?;
+
+                    let takes_self = arguments.first().map(|x| x.is_self()).unwrap_or(false);
+                    let name = name.r#type.get_id_val().unwrap_or_default();
+                    enclosing.declare_method(key.clone(), name.clone(), function_type, takes_self);
+                    signatures.push((name, arguments, returns, takes_self));
+                }
+            }
+
+            let bodies = functions.iter().filter_map(|function| match &function.kind {
+                ExpressionKind::Fn { name : Some(_), generics, result, body, .. } => Some((generics, result, body)),
+                _ => None
+            });
+            for ((name, arguments, returns, takes_self), (generics, result, body)) in signatures.into_iter().zip(bodies){
+                let body_type = check_function_body(&arguments, &returns, body, &mut generic_scope(generics, enclosing))?;
+
+                let function_type = settle(&arguments, &returns, result, body, &body_type, enclosing);
+                enclosing.declare_method(key.clone(), name, function_type, takes_self);
+            }
+
+            Ok(Type::NullType)
+        },
+
+        ExpressionKind::Unary { operator, right } => {
+            let right_type = check_expression(right, enclosing)?;
+
+            if enclosing.is_unbound(&right_type) {
+                return infer_operation(operator, right_type, Type::NullType, enclosing)
+            }
+
+            if right_type == Type::AnyType {
+                return Ok(Type::AnyType)
+            }
+
+            enclosing.get_operation(operator.r#type.clone(), right_type.clone(), Type::NullType)
+                .ok_or(Error::InvalidOperands{
+                    operator : operator.clone(),
+                    operands : vec![right_type.to_string()]
+                })
+        },
+
+        ExpressionKind::Is { value, r#type } => {
+            check_expression(value, enclosing)?;
+            enclosing.validate(r#type, expression.span)?;
+
+            Ok(Type::BoolType)
+        },
+
+        ExpressionKind::Binary { left, operator, right } => {
+            match operator.r#type {
+                TokenType::EQEQ | TokenType::NEQ => {
+                    check_expression(left, enclosing)?;
+                    check_expression(right, enclosing)?;
+                    Ok(Type::BoolType)
+                },
+                TokenType::PLUSEQ | TokenType::MINUSEQ | TokenType::STAREQ | TokenType::SLASHEQ => {
+                    check_compound_assign(left, operator, right, enclosing)
+                },
+                //the right side only runs once the left side was true (and) or false (or)
+                TokenType::AND | TokenType::OR => {
+                    let left_type = check_expression(left, enclosing)?;
+                    let (when_true, when_false) = narrowings(left, enclosing);
+                    let known = if operator.r#type == TokenType::AND { when_true } else { when_false };
+                    let right_type = check_expression(right, &mut narrowed(enclosing, &known))?;
+
+                    check_operation(operator, left_type, right_type, enclosing)
+                },
+                _ => {
+                    let left_type = check_expression(left, enclosing)?;
+                    let right_type = check_expression(right, enclosing)?;
+
+                    check_operation(operator, left_type, right_type, enclosing)
+                }
+            }
+        },
+
+        ExpressionKind::Block { expressions } => {
+            let mut env = TypeEnvironment::enclose(enclosing.clone());
+
+            check_expressions(expressions, &mut env)
+        },
+
+        ExpressionKind::If { condition, if_block, else_if_blocks, else_block } => {
+            //every branch after the first is only reached when the conditions before were false
+            let mut otherwise = TypeEnvironment::enclose(enclosing.clone());
+            let mut branches = Vec::new();
+
+            let mut chain : Vec<(&Expression, &Expression)> = vec![(condition, if_block)];
+            chain.extend(else_if_blocks.iter().map(|(condition, block)| (condition, block)));
+
+            for (condition, block) in chain{
+                check_condition(condition, &mut otherwise)?;
+                let (when_true, when_false) = narrowings(condition, &mut otherwise);
+
+                branches.push(check_expression(block, &mut narrowed(&otherwise, &when_true))?);
+                otherwise = narrowed(&otherwise, &when_false);
+            }
+
+            match else_block {
+                Some(block) => branches.push(check_expression(block, &mut otherwise)?),
+                //without an else the if might not produce anything
+                None => branches.push(Type::NullType)
+            }
+
+            Ok(union_of(branches))
+        },
+
+        ExpressionKind::Match { value, arms } => {
+            let value_type = check_expression(value, enclosing)?;
+            let cases = cases_of(&value_type, enclosing);
+            let mut covered = vec![false; cases.len()];
+            let mut unguarded = Vec::new();
+            let mut results = Vec::new();
+
+            for arm in arms{
+                let touched = (0..cases.len()).filter(|index| touches(&arm.pattern, &cases[*index], enclosing)).collect::<Vec<usize>>();
+
+                //check_pattern knows best what is wrong with the pattern
+                if touched.is_empty() {
+                    check_pattern(&arm.pattern, &value_type, &mut Vec::new(), enclosing)?;
+                    return Err(Error::PatternMismatch{
+                        found : value_type,
+                        span : arm.pattern.span
+                    })
+                }
+
+                if touched.iter().all(|index| covered[*index]) {
+                    return Err(Error::UnreachablePattern(arm.pattern.span))
+                }
+
+                //what the value can still be once the arms before did not match
+                let remaining = union_of(touched.iter().filter(|index| !covered[**index]).map(|index| cases[*index].r#type()).collect());
+
+                let mut bindings = Vec::new();
+                check_pattern(&arm.pattern, &remaining, &mut bindings, enclosing)?;
+
+                let mut env = TypeEnvironment::enclose(enclosing.clone());
+                for (name, binding_type) in bindings{
+                    env.assign_type(name, binding_type)?;
+                }
+
+                if let Some(guard) = &arm.guard{
+                    check_condition(guard, &mut env)?;
+                }
+                results.push(check_expression(&arm.body, &mut env)?);
+
+                //a guard can always fail, so guarded arms do not cover anything
+                if arm.guard.is_none() {
+                    unguarded.push(&arm.pattern);
+
+                    for (index, case) in cases.iter().enumerate(){
+                        covered[index] = covered[index] || covers(&arm.pattern, case, enclosing) || covers_lengths(&unguarded, case, enclosing);
+                    }
+                }
+            }
+
+            let missing = cases.iter().zip(covered.iter())
+                .filter(|(_, covered)| !**covered)
+                .map(|(case, _)| case.to_string())
+                .collect::<Vec<String>>();
+
+            if !missing.is_empty() {
+                return Err(Error::NonExhaustiveMatch{
+                    missing,
+                    span : value.span
+                })
+            }
+
+            Ok(union_of(results))
+        },
+
+        ExpressionKind::While { condition, block } => {
+            check_condition(condition, enclosing)?;
+            let (when_true, _) = narrowings(condition, enclosing);
+            check_expression(block, &mut narrowed(enclosing, &when_true))?;
+
+            Ok(Type::NullType)
+        },
+
+        ExpressionKind::For { binding, range, block } => {
+            let element_type = match range {
+                ForRange::In(iterable) => {
+                    let iterable_type = check_expression(iterable, enclosing)?;
+                    match enclosing.resolve(&iterable_type) {
+                        Type::ArrayType(inner) => *inner,
+                        Type::StrType => Type::StrType,
+                        Type::ObjectType { .. } => Type::StrType,
+                        Type::AnyType => Type::AnyType,
+                        other => return Err(Error::NotIterable{
+                            found : other.to_string(),
+                            span : iterable.span
+                        })
+                    }
+                },
+                ForRange::To { start, end, step } => {
+                    let mut bounds = vec![start, end];
+                    if let Some(step) = step{
+                        bounds.push(step);
+                    }
+
+                    for bound in bounds{
+                        let bound_type = check_expression(bound, enclosing)?;
+                        enclosing.expect(&Type::NumType, &bound_type, bound.span)?;
+                    }
+
+                    Type::NumType
+                }
+            };
+
+            enclosing.validate(&binding.r#type, binding.name.span)?;
+            enclosing.expect(&binding.r#type, &element_type, binding.name.span)?;
+
+            //an unannotated binding takes the type of the elements
+            let binding_type = match binding.annotated {
+                true => binding.r#type.clone(),
+                false => element_type
+            };
+
+            let mut env = TypeEnvironment::enclose(enclosing.clone());
+            env.assign_type(binding.name.r#type.get_id_val().unwrap_or_default(), binding_type)?;
+            check_expression(block, &mut env)?;
+
+            Ok(Type::NullType)
+        },
+
+        ExpressionKind::StructUsage { struct_name, variant, fields, values } => {
+            let name = struct_name.r#type.get_id_val().unwrap_or_default();
+            let parameters = enclosing.get_parameters(name.clone());
+            let key = name.clone();
+
+            let (name, keys, types) = match variant {
+                None => {
+                    let (keys, types) = struct_of(struct_name, enclosing)?;
+                    (name, keys, types)
+                },
+                Some(variant) => {
+                    let path = format!("{}::{}", name, variant.r#type.get_id_val().unwrap_or_default());
+
+                    match variant_of(struct_name, variant, enclosing)? {
+                        Payload::Fields { keys, types } => (path, keys, types),
+                        payload => return Err(Error::PayloadMismatch{
+                            usage : payload.usage(&path),
+                            variant : path,
+                            span : variant.span
+                        })
+                    }
+                }
+            };
+
+            let mut given : Vec<&Token> = Vec::new();
+            //the parameters of generic structs are inferred from all the values before any of them is checked
+            let mut bindings = HashMap::new();
+            let mut checked = Vec::new();
+            for (field, value) in fields.iter().zip(values.iter()){
+                let key = field.r#type.get_id_val().unwrap_or_default();
+
+                if let Some(previous) = given.iter().find(|x| x.r#type == field.r#type) {
+                    return Err(Error::DuplicateField{
+                        field : key,
+                        span : field.span,
+                        previous : previous.span
+                    })
+                }
+
+                let index = match keys.iter().position(|x| *x == key) {
+                    Some(index) => index,
+                    None => return Err(Error::FieldNotFound{
+                        field : key,
+                        span : field.span
+                    })
+                };
+
+                let value_type = check_expression(value, enclosing)?;
+                infer(&types[index], &value_type, &mut bindings, enclosing);
+                checked.push((index, value_type, value.span));
+                given.push(field);
+            }
+
+            for (index, value_type, value_span) in checked{
+                enclosing.expect(&substitute(&types[index], &bindings), &value_type, value_span)?;
+            }
+
+            let missing = keys.iter()
+                .filter(|key| !given.iter().any(|x| x.r#type.get_id_val().as_ref() == Some(*key)))
+                .cloned()
+                .collect::<Vec<String>>();
+
+            if !missing.is_empty() {
+                return Err(Error::MissingFields{
+                    name,
+                    fields : missing,
+                    span : struct_name.span
+                })
+            }
+
+            if parameters.is_empty() {
+                return Ok(Type::CustomType(key))
+            }
+
+            Ok(Type::Applied{
+                name : key,
+                arguments : parameters.iter().map(|x| bindings.get(x).cloned().unwrap_or(Type::AnyType)).collect()
+            })
+        },
+
+        ExpressionKind::Return(value) => {
+            let value_type = check_expression(value, enclosing)?;
+
+            match enclosing.get_return_type() {
+                //functions without a result type return everything they return
+                Some(Type::Inferred(id)) => {
+                    enclosing.widen_unknown(id, &value_type);
+                    Ok(Type::NullType)
+                },
+                Some(returns) => {
+                    let checked = enclosing.expect(&returns, &value_type, value.span);
+                    noting_inferred(checked, value, enclosing)?;
+                    Ok(Type::NullType)
+                },
+                None => Err(Error::InvalidControlFlow{
+                    keyword : "return".to_string(),
+                    span
+                })
+            }
+        },
+
+        ExpressionKind::Break | ExpressionKind::Continue => Ok(Type::NullType),
+        //whatever failed to parse could have been anything, so it should not cause more errors
+        ExpressionKind::Error(_) => Ok(Type::AnyType),
+    }
+}
+
+//what the struct or enum called name was declared as, errors if there is no such type or it is an alias
+fn nominal_of(name : &Token, enclosing : &mut TypeEnvironment) -> Result<Type, Error>{
+    let key = name.r#type.get_id_val().unwrap_or_default();
+    enclosing.validate(&Type::CustomType(key.clone()), name.span)?;
+
+    match enclosing.get_declared_type(key.clone()) {
+        Some(declared @ (Type::Struct { .. } | Type::Enum { .. })) => Ok(declared),
+        _ => Err(Error::NotAStruct{
+            name : key,
+            span : name.span
+        })
+    }
+}
+
+//the fields of the struct called name
+fn struct_of(name : &Token, enclosing : &mut TypeEnvironment) -> Result<(Vec<String>, Vec<Type>), Error>{
+    match nominal_of(name, enclosing)? {
+        Type::Struct { keys, types } => Ok((keys, types)),
+        _ => Err(Error::NotAStruct{
+            name : name.r#type.get_id_val().unwrap_or_default(),
+            span : name.span
+        })
+    }
+}
+
+//the payload of enum_name::variant
+fn variant_of(enum_name : &Token, variant : &Token, enclosing : &mut TypeEnvironment) -> Result<Payload, Error>{
+    let name = variant.r#type.get_id_val().unwrap_or_default();
+
+    let (variants, payloads) = match nominal_of(enum_name, enclosing)? {
+        Type::Enum { variants, payloads } => (variants, payloads),
+        _ => (Vec::new(), Vec::new())
+    };
+
+    match variants.iter().position(|x| *x == name) {
+        Some(index) => Ok(payloads[index].clone()),
+        None => Err(Error::VariantNotFound{
+            enum_name : enum_name.r#type.get_id_val().unwrap_or_default(),
+            variant : name,
+            variants,
+            span : variant.span
+        })
+    }
+}
+
+//one of the things a matched value can be, a match has to cover every case of the value's type
+#[derive(Clone, Debug, PartialEq)]
+enum Case{
+    Type(Type),
+    Bool(bool),
+    //the enum, the variant and its payload
+    Variant(String, String, Payload)
+}
+
+impl Case{
+    fn r#type(&self) -> Type{
+        match self {
+            Case::Type(case_type) => case_type.clone(),
+            Case::Bool(_) => Type::BoolType,
+            Case::Variant(name, ..) => Type::CustomType(name.to_string())
+        }
+    }
+}
+
+//cases are shown as the pattern that would be missing
+impl std::fmt::Display for Case{
+    fn fmt(&self, f : &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Case::Type(Type::AnyType) => write!(f, "_"),
+            Case::Type(Type::NullType) => write!(f, "nil"),
+            Case::Type(case_type) => write!(f, "_ : {}", case_type),
+            Case::Bool(bool) => write!(f, "{}", bool),
+            Case::Variant(name, variant, payload) => {
+                match payload {
+                    Payload::Unit => write!(f, "{}::{}", name, variant),
+                    Payload::Tuple(types) => write!(f, "{}::{}({})", name, variant, vec!["_"; types.len()].join(", ")),
+                    Payload::Fields { .. } => write!(f, "{}::{} {{ .. }}", name, variant)
+                }
+            }
+        }
+    }
+}
+
+//splits a type into its cases: the options of unions, the variants of enums and true and false
+fn cases_of(r#type : &Type, enclosing : &mut TypeEnvironment) -> Vec<Case>{
+    match enclosing.resolve(r#type) {
+        Type::UnionType(options) => {
+            let mut cases = Vec::new();
+            for option in options{
+                cases.extend(cases_of(&option, enclosing));
+            }
+            cases
+        },
+        Type::CustomType(name) => match enclosing.get_declared_type(name.clone()) {
+            Some(Type::Enum { variants, payloads }) => variants.into_iter().zip(payloads)
+                .map(|(variant, payload)| Case::Variant(name.clone(), variant, payload))
+                .collect(),
+            _ => vec![Case::Type(Type::CustomType(name))]
+        },
+        Type::BoolType => vec![Case::Bool(true), Case::Bool(false)],
+        other => vec![Case::Type(other)]
+    }
+}
+
+//the type of a literal pattern, the type checker makes sure it really is one
+fn literal_type(literal : &Expression) -> Type{
+    match literal.kind {
+        ExpressionKind::LiteralNum(_) | ExpressionKind::Unary { .. } => Type::NumType,
+        ExpressionKind::LiteralStr(_) => Type::StrType,
+        ExpressionKind::LiteralBool(_) => Type::BoolType,
+        ExpressionKind::LiteralNil => Type::NullType,
+        _ => Type::AnyType
+    }
+}
+
+//whether the pattern can match some of the values of the case
+fn touches(pattern : &Pattern, case : &Case, enclosing : &mut TypeEnvironment) -> bool{
+    match (&pattern.kind, case) {
+        (PatternKind::Wildcard | PatternKind::Binding(_), _) | (_, Case::Type(Type::AnyType)) => true,
+        (PatternKind::Typed { r#type, .. }, case) => {
+            let case_type = case.r#type();
+            enclosing.is_assignable(r#type, &case_type) || enclosing.is_assignable(&case_type, r#type)
+        },
+        (PatternKind::Literal(literal), Case::Bool(bool)) => literal.kind == ExpressionKind::LiteralBool(*bool),
+        (PatternKind::Literal(literal), Case::Type(case_type)) => enclosing.is_assignable(case_type, &literal_type(literal)),
+        (PatternKind::Struct { name, .. }, Case::Type(Type::CustomType(case_name) | Type::Applied { name : case_name, .. })) => {
+            name.r#type.get_id_val().as_ref() == Some(case_name)
+        },
+        (PatternKind::Object(_), Case::Type(case_type)) => {
+            match enclosing.resolve(case_type) {
+                Type::ObjectType { .. } => true,
+                Type::CustomType(name) | Type::Applied { name, .. } => matches!(enclosing.get_declared_type(name), Some(Type::Struct { .. })),
+                _ => false
+            }
+        },
+        (PatternKind::Array { .. }, Case::Type(case_type)) => matches!(enclosing.resolve(case_type), Type::ArrayType(_)),
+        (PatternKind::Variant { enum_name, variant, .. }, Case::Variant(case_name, case_variant, _)) => {
+            enum_name.r#type.get_id_val().as_ref() == Some(case_name) && variant.r#type.get_id_val().as_ref() == Some(case_variant)
+        },
+        _ => false
+    }
+}
+
+//whether the pattern matches all values of the case, nested patterns only count if they match everything
+fn covers(pattern : &Pattern, case : &Case, enclosing : &mut TypeEnvironment) -> bool{
+    match (&pattern.kind, case) {
+        (PatternKind::Wildcard | PatternKind::Binding(_), _) => true,
+        (PatternKind::Typed { r#type, .. }, Case::Type(Type::AnyType)) => enclosing.resolve(r#type) == Type::AnyType,
+        (PatternKind::Typed { r#type, .. }, case) => enclosing.is_assignable(r#type, &case.r#type()),
+        (PatternKind::Literal(literal), Case::Bool(bool)) => literal.kind == ExpressionKind::LiteralBool(*bool),
+        (PatternKind::Literal(literal), Case::Type(Type::NullType)) => literal.kind == ExpressionKind::LiteralNil,
+        (PatternKind::Struct { fields, .. } | PatternKind::Object(fields), Case::Type(case_type)) if touches(pattern, case, enclosing) => {
+            fields.iter().all(|(key, field)| {
+                let key = key.r#type.get_id_val().unwrap_or_default();
+                match field_type(case_type, &key, pattern.span, enclosing) {
+                    Ok(field_type) => irrefutable(field, &field_type, enclosing),
+                    Err(_) => false
+                }
+            })
+        },
+        (PatternKind::Array { .. }, _) => covers_lengths(&[pattern], case, enclosing),
+        (PatternKind::Variant { payload, .. }, Case::Variant(_, _, declared)) if touches(pattern, case, enclosing) => {
+            match (payload, declared) {
+                (PatternPayload::Unit, Payload::Unit) => true,
+                (PatternPayload::Tuple(patterns), Payload::Tuple(types)) => {
+                    patterns.len() == types.len() && patterns.iter().zip(types.iter()).all(|(pattern, r#type)| irrefutable(pattern, r#type, enclosing))
+                },
+                (PatternPayload::Fields(fields), Payload::Fields { keys, types }) => {
+                    fields.iter().all(|(key, field)| {
+                        match keys.iter().position(|x| key.r#type.get_id_val().as_ref() == Some(x)) {
+                            Some(index) => irrefutable(field, &types[index], enclosing),
+                            None => false
+                        }
+                    })
+                },
+                _ => false
+            }
+        },
+        _ => false
+    }
+}
+
+//arrays are covered by arms for every length together, like [] and [first, ...rest]
+fn covers_lengths(patterns : &[&Pattern], case : &Case, enclosing : &mut TypeEnvironment) -> bool{
+    let inner = match case {
+        Case::Type(case_type) => match enclosing.resolve(case_type) {
+            Type::ArrayType(inner) => *inner,
+            _ => return false
+        },
+        _ => return false
+    };
+
+    let mut lengths = Vec::new();
+    let mut shortest_rest = None;
+    for pattern in patterns{
+        if let PatternKind::Array { elements, rest } = &pattern.kind {
+            if !elements.iter().all(|element| irrefutable(element, &inner, enclosing)) {
+                continue;
+            }
+
+            match rest {
+                None => lengths.push(elements.len()),
+                Some(rest) if irrefutable(rest, &Type::ArrayType(Box::new(inner.clone())), enclosing) => {
+                    shortest_rest = Some(shortest_rest.unwrap_or(usize::MAX).min(elements.len()));
+                },
+                Some(_) => ()
+            }
+        }
+    }
+
+    match shortest_rest {
+        Some(shortest) => (0..shortest).all(|length| lengths.contains(&length)),
+        None => false
+    }
+}
+
+fn irrefutable(pattern : &Pattern, r#type : &Type, enclosing : &mut TypeEnvironment) -> bool{
+    cases_of(r#type, enclosing).iter().all(|case| covers(pattern, case, enclosing))
+}
+
+//checks that the pattern fits the type and collects the names it binds
+fn check_pattern(pattern : &Pattern, expected : &Type, bindings : &mut Vec<(String, Type)>, enclosing : &mut TypeEnvironment) -> Result<(), Error>{
+    //names that do not exist are a better error than a pattern that does not fit
+    match &pattern.kind {
+        PatternKind::Typed { r#type, .. } => enclosing.validate(r#type, pattern.span)?,
+        PatternKind::Struct { name, .. } => {
+            struct_of(name, enclosing)?;
+        },
+        PatternKind::Variant { enum_name, variant, .. } => {
+            variant_of(enum_name, variant, enclosing)?;
+        },
+        _ => ()
+    }
+
+    let touched = cases_of(expected, enclosing).into_iter()
+        .filter(|case| touches(pattern, case, enclosing))
+        .map(|case| case.r#type())
+        .collect::<Vec<Type>>();
+
+    if touched.is_empty() {
+        return Err(Error::PatternMismatch{
+            found : expected.clone(),
+            span : pattern.span
+        })
+    }
+
+    match &pattern.kind {
+        PatternKind::Wildcard => (),
+        PatternKind::Binding(name) => bindings.push((name.to_string(), union_of(touched))),
+        PatternKind::Literal(literal) => {
+            check_expression(literal, enclosing)?;
+        },
+        PatternKind::Typed { name, r#type } => {
+            if let Some(name) = name{
+                bindings.push((name.to_string(), r#type.clone()));
+            }
+        },
+        PatternKind::Struct { name, fields } => {
+            //a generic struct has the fields of the version the value is
+            let arguments = touched.iter().find_map(|x| match x {
+                Type::Applied { arguments, .. } => Some(arguments.clone()),
+                _ => None
+            }).unwrap_or_default();
+
+            let (keys, types) = struct_of(name, enclosing)?;
+            let key = name.r#type.get_id_val().unwrap_or_default();
+            let types = types.iter().map(|x| enclosing.apply(key.clone(), x, &arguments)).collect::<Vec<Type>>();
+            check_field_patterns(fields, &keys, &types, bindings, enclosing)?;
+        },
+        PatternKind::Object(fields) => {
+            for (key, field) in fields{
+                let name = key.r#type.get_id_val().unwrap_or_default();
+
+                let mut options = Vec::new();
+                for option in touched.iter(){
+                    options.push(field_type(option, &name, key.span, enclosing)?);
+                }
+
+                check_pattern(field, &union_of(options), bindings, enclosing)?;
+            }
+        },
+        PatternKind::Array { elements, rest } => {
+            let inner = union_of(touched.iter().map(|option| match enclosing.resolve(option) {
+                Type::ArrayType(inner) => *inner,
+                _ => Type::AnyType
+            }).collect());
+
+            for element in elements{
+                check_pattern(element, &inner, bindings, enclosing)?;
+            }
+
+            if let Some(rest) = rest{
+                check_pattern(rest, &Type::ArrayType(Box::new(inner)), bindings, enclosing)?;
+            }
+        },
+        PatternKind::Variant { enum_name, variant, payload } => {
+            let declared = variant_of(enum_name, variant, enclosing)?;
+
+            match (payload, &declared) {
+                (PatternPayload::Unit, Payload::Unit) => (),
+                (PatternPayload::Tuple(patterns), Payload::Tuple(types)) if patterns.len() == types.len() => {
+                    for (pattern, r#type) in patterns.iter().zip(types.iter()){
+                        check_pattern(pattern, r#type, bindings, enclosing)?;
+                    }
+                },
+                (PatternPayload::Fields(fields), Payload::Fields { keys, types }) => {
+                    check_field_patterns(fields, keys, types, bindings, enclosing)?;
+                },
+                _ => {
+                    let path = format!("{}::{}", enum_name.r#type.get_id_val().unwrap_or_default(), variant.r#type.get_id_val().unwrap_or_default());
+                    return Err(Error::PayloadMismatch{
+                        usage : declared.usage(&path),
+                        variant : path,
+                        span : pattern.span
+                    })
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+//the fields of struct and variant patterns against the declared fields
+fn check_field_patterns(fields : &[(Token, Pattern)], keys : &[String], types : &[Type], bindings : &mut Vec<(String, Type)>, enclosing : &mut TypeEnvironment) -> Result<(), Error>{
+    for (key, field) in fields{
+        let name = key.r#type.get_id_val().unwrap_or_default();
+
+        match keys.iter().position(|x| *x == name) {
+            Some(index) => check_pattern(field, &types[index], bindings, enclosing)?,
+            None => return Err(Error::FieldNotFound{
+                field : name,
+                span : key.span
+            })
+        }
+    }
+
+    Ok(())
+}
+
+fn check_condition(condition : &Expression, enclosing : &mut TypeEnvironment) -> Result<(), Error>{
+    let condition_type = check_expression(condition, enclosing)?;
+
+    if enclosing.is_assignable(&Type::BoolType, &condition_type) {
+        return Ok(())
+    }
+
+    Err(Error::InvalidCondition{
+        found : condition_type.to_string(),
+        span : condition.span
+    })
+}
+
+//the narrower types a condition gives to variables, first for when it is true and then for when it is false
+type Narrowings = (Vec<(String, Type)>, Vec<(String, Type)>);
+
+fn narrowings(condition : &Expression, enclosing : &mut TypeEnvironment) -> Narrowings{
+    match &condition.kind {
+        ExpressionKind::Grp { inner } => narrowings(inner, enclosing),
+        ExpressionKind::Unary { operator, right } if operator.r#type == TokenType::BANG => {
+            let (when_true, when_false) = narrowings(right, enclosing);
+            (when_false, when_true)
+        },
+
+        //the right side of and/or is only looked at when the left side was true/false
+        ExpressionKind::Binary { left, operator, right } if operator.r#type == TokenType::AND => {
+            let (left_true, left_false) = narrowings(left, enclosing);
+            let (right_true, right_false) = narrowings(right, &mut narrowed(enclosing, &left_true));
+
+            ([left_true, right_true].concat(), either(left_false, right_false))
+        },
+        ExpressionKind::Binary { left, operator, right } if operator.r#type == TokenType::OR => {
+            let (left_true, left_false) = narrowings(left, enclosing);
+            let (right_true, right_false) = narrowings(right, &mut narrowed(enclosing, &left_false));
+
+            (either(left_true, right_true), [left_false, right_false].concat())
+        },
+
+        ExpressionKind::Binary { left, operator, right } if matches!(operator.r#type, TokenType::EQEQ | TokenType::NEQ) => {
+            let checked = match (&left.kind, &right.kind) {
+                //x == nil
+                (ExpressionKind::LiteralID(name), ExpressionKind::LiteralNil)
+                    | (ExpressionKind::LiteralNil, ExpressionKind::LiteralID(name)) => Some((name, Type::NullType)),
+                //type(x) == "num"
+                (ExpressionKind::FunctionCall { function, arguments }, ExpressionKind::LiteralStr(type_name))
+                    | (ExpressionKind::LiteralStr(type_name), ExpressionKind::FunctionCall { function, arguments }) => {
+                    match (&function.kind, arguments.as_slice()) {
+                        (ExpressionKind::LiteralID(function_name), [argument]) if function_name == "type" => match &argument.kind {
+                            ExpressionKind::LiteralID(name) => Some((name, type_named(type_name))),
+                            _ => None
+                        },
+                        _ => None
+                    }
+                },
+                _ => None
+            };
+
+            let split = match checked {
+                Some((name, checked_type)) => split(name, &checked_type, enclosing),
+                None => (vec![], vec![])
+            };
+
+            match operator.r#type {
+                TokenType::NEQ => (split.1, split.0),
+                _ => split
+            }
+        },
+
+        //x is num
+        ExpressionKind::Is { value, r#type } => match &value.kind {
+            ExpressionKind::LiteralID(name) => split(name, r#type, enclosing),
+            _ => (vec![], vec![])
+        },
+
+        _ => (vec![], vec![])
+    }
+}
+
+//splits the type of a variable into what fits the checked type at runtime and what does not, like the
+//interpreter arrays only fit arrays and structs only themselves, the element and field types do not matter
+fn split(name : &str, checked_type : &Type, enclosing : &mut TypeEnvironment) -> Narrowings{
+    let declared = match enclosing.get_type(name.to_string()) {
+        Some(declared) => declared,
+        None => return (vec![], vec![])
+    };
+
+    let checked = options_of(checked_type, enclosing);
+    let mut fitting = Vec::new();
+    let mut rest = Vec::new();
+
+    for option in options_of(&declared, enclosing){
+        if option == Type::AnyType {
+            //we only know as much about untyped values as the check itself looked at
+            fitting.extend(checked.iter().map(shallow));
+            rest.push(Type::AnyType);
+        } else if checked.iter().any(|x| *x == Type::AnyType || same_shape(x, &option)) {
+            fitting.push(option);
+        } else {
+            rest.push(option);
+        }
+    }
+
+    //an empty side means the branch can never be taken, there is nothing to narrow to then
+    let mut narrowings = (vec![], vec![]);
+    if !fitting.is_empty() {
+        narrowings.0.push((name.to_string(), union_of(fitting)));
+    }
+    if !rest.is_empty() {
+        narrowings.1.push((name.to_string(), union_of(rest)));
+    }
+
+    narrowings
+}
+
+//the options of a (union) type with every alias resolved
+fn options_of(r#type : &Type, enclosing : &mut TypeEnvironment) -> Vec<Type>{
+    match enclosing.resolve(r#type) {
+        Type::UnionType(options) => options.iter().flat_map(|option| options_of(option, enclosing)).collect(),
+        other => vec![other]
+    }
+}
+
+//whether values of both types would be told apart by the interpreter (see Value::matches_type)
+fn same_shape(a : &Type, b : &Type) -> bool{
+    match (a, b) {
+        (Type::ArrayType(_), Type::ArrayType(_)) => true,
+        (Type::ObjectType { .. }, Type::ObjectType { .. }) => true,
+        (Type::FunctionType { .. }, Type::FunctionType { .. }) => true,
+        _ => a == b
+    }
+}
+
+//what a value is known to be after it passed a check against the type
+fn shallow(r#type : &Type) -> Type{
+    match r#type {
+        Type::ArrayType(_) => Type::ArrayType(Box::new(Type::AnyType)),
+        Type::ObjectType { .. } | Type::FunctionType { .. } => Type::AnyType,
+        other => other.clone()
+    }
+}
+
+//the type behind a name the builtin type function gives back
+fn type_named(type_name : &str) -> Type{
+    match type_name {
+        "num" => Type::NumType,
+        "string" => Type::StrType,
+        "bool" => Type::BoolType,
+        "nil" => Type::NullType,
+        "array" => Type::ArrayType(Box::new(Type::AnyType)),
+        "object" => Type::ObjectType{
+            keys : vec![],
+            types : vec![]
+        },
+        "fn" => Type::FunctionType{
+            arguments : vec![],
+            returns : Box::new(Type::AnyType)
+        },
+        name => Type::CustomType(name.to_string())
+    }
+}
+
+//when either of two conditions holds we only know something about names both narrow
+fn either(left : Vec<(String, Type)>, right : Vec<(String, Type)>) -> Vec<(String, Type)>{
+    left.into_iter().filter_map(|(name, left_type)| {
+        right.iter().rev().find(|(other, _)| *other == name)
+            .map(|(_, right_type)| (name, union_of(vec![left_type, right_type.clone()])))
+    }).collect()
+}
+
+//a scope inside of enclosing where the names have their narrowed types
+fn narrowed(enclosing : &TypeEnvironment, narrowings : &[(String, Type)]) -> TypeEnvironment{
+    let mut env = TypeEnvironment::enclose(enclosing.clone());
+    for (name, narrowed_type) in narrowings{
+        env.narrow(name.to_string(), narrowed_type.clone());
+    }
+
+    env
+}
+
+//checks the body of functions and overloads with the arguments declared and the return type known
+fn check_function_body(arguments : &[TypedName], returns : &Type, body : &Expression, enclosing : &mut TypeEnvironment) -> Result<Type, Error>{
+    let mut env = TypeEnvironment::enclose(enclosing.clone());
+    env.returns = Some(returns.clone());
+
+    for argument in arguments{
+        env.assign_type(argument.name.r#type.get_id_val().unwrap_or_default(), argument.r#type.clone())?;
+    }
+
+    let body_type = check_expression(body, &mut env)?;
+
+    //functions without a block body (fn(x) x + 1) return their body
+    if !matches!(body.kind, ExpressionKind::Block { .. }) {
+        env.expect(returns, &body_type, body.span)?;
+    }
+
+    Ok(body_type)
+}
+
+//arguments and results without annotations are worked out from the body, until then they are unknowns
+fn signature_of(arguments : &[TypedName], result : &Option<Type>, span : Span, enclosing : &mut TypeEnvironment) -> (Vec<TypedName>, Type){
+    let arguments = arguments.iter().map(|argument| match argument.annotated {
+        true => argument.clone(),
+        false => TypedName{
+            r#type : enclosing.unknown(argument.name.span),
+            ..argument.clone()
+        }
+    }).collect();
+    let returns = result.clone().unwrap_or_else(|| enclosing.unknown(span));
+
+    (arguments, returns)
+}
+
+//the type of a function once its body was checked, what the body did not work out could be anything
+fn settle(arguments : &[TypedName], returns : &Type, result : &Option<Type>, body : &Expression, body_type : &Type, enclosing : &mut TypeEnvironment) -> Type{
+    //the body gives back what it ends with, unless it ends by returning
+    let falls_through = match &body.kind {
+        ExpressionKind::Block { expressions } => !matches!(expressions.last().map(|x| &x.kind), Some(ExpressionKind::Return(_))),
+        _ => true
+    };
+    if let (None, Type::Inferred(id), true) = (result, returns, falls_through) {
+        enclosing.widen_unknown(*id, body_type);
+    }
+
+    for unknown in arguments.iter().map(|x| &x.r#type).chain([returns]){
+        if let Type::Inferred(id) = unknown {
+            enclosing.give_up(*id);
+        }
+    }
+
+    enclosing.fill_in(&Type::FunctionType{
+        arguments : arguments.iter().map(|x| x.r#type.clone()).collect(),
+        returns : Box::new(returns.clone())
+    })
+}
+
+//inside of a generic function the parameters are types of their own, so let x : T = ... works
+fn generic_scope(generics : &[String], enclosing : &TypeEnvironment) -> TypeEnvironment{
+    let mut env = TypeEnvironment::enclose(enclosing.clone());
+    for generic in generics{
+        env.declare_type(generic.to_string(), Type::Param(generic.to_string()));
+    }
+
+    env
+}
+
+//checks the arguments of a call against the type of the function and gives back what it returns
+fn check_call(function_type : &Type, function_span : Span, arguments : &[Expression], span : Span, enclosing : &mut TypeEnvironment) -> Result<Type, Error>{
+    let mut argument_types = Vec::new();
+    for argument in arguments{
+        argument_types.push(check_expression(argument, enclosing)?);
+    }
+
+    match enclosing.resolve(function_type) {
+        Type::AnyType => Ok(Type::AnyType),
+        Type::FunctionType { arguments : expected, returns } => {
+            if expected.len() != argument_types.len(){
+                return Err(Error::ArityMismatch{
+                    expected : expected.len(),
+                    found : argument_types.len(),
+                    span
+                })
+            }
+
+            //the parameters of generic functions are whatever the arguments make them
+            let mut bindings = HashMap::new();
+            for (expected_type, found_type) in expected.iter().zip(argument_types.iter()){
+                infer(expected_type, found_type, &mut bindings, enclosing);
+            }
+
+            for ((expected_type, found_type), argument) in expected.iter().zip(argument_types.iter()).zip(arguments.iter()){
+                let checked = enclosing.expect(&substitute(expected_type, &bindings), found_type, argument.span);
+                noting_inferred(checked, argument, enclosing)?;
+            }
+
+            Ok(substitute(&returns, &bindings))
+        },
+        other => Err(Error::NotCallable{
+            found : other.to_string(),
+            span : function_span
+        })
+    }
+}
+
+//finds out what the parameters inside of expected have to be for found to fit it, a parameter that
+//is found as different types becomes all of them
+fn infer(expected : &Type, found : &Type, bindings : &mut HashMap<String, Type>, enclosing : &mut TypeEnvironment){
+    let found = enclosing.resolve(found);
+
+    match (enclosing.resolve(expected), &found) {
+        (Type::Param(name), _) => {
+            let bound = match bindings.get(&name) {
+                None => found,
+                Some(bound) if enclosing.is_assignable(bound, &found) => bound.clone(),
+                Some(bound) if enclosing.is_assignable(&found, bound) => found,
+                Some(bound) => union_of(vec![bound.clone(), found])
+            };
+            bindings.insert(name, bound);
+        },
+        (Type::ArrayType(inner), Type::ArrayType(found_inner)) => infer(&inner, found_inner, bindings, enclosing),
+        (Type::Applied { name, arguments }, Type::Applied { name : found_name, arguments : found_arguments }) if name == *found_name => {
+            for (argument, found_argument) in arguments.iter().zip(found_arguments.iter()){
+                infer(argument, found_argument, bindings, enclosing);
+            }
+        },
+        (Type::ObjectType { keys, types }, Type::ObjectType { keys : found_keys, types : found_types }) => {
+            for (key, field_type) in keys.iter().zip(types.iter()){
+                if let Some(index) = found_keys.iter().position(|x| x == key){
+                    infer(field_type, &found_types[index], bindings, enclosing);
+                }
+            }
+        },
+        (Type::FunctionType { arguments, returns }, Type::FunctionType { arguments : found_arguments, returns : found_returns }) => {
+            for (argument, found_argument) in arguments.iter().zip(found_arguments.iter()){
+                infer(argument, found_argument, bindings, enclosing);
+            }
+            infer(&returns, found_returns, bindings, enclosing);
+        },
+        //T | nil found as num | nil makes T a num, whatever the other options do not take is left for the parameter
+        (Type::UnionType(options), _) => {
+            let (parameters, others) : (Vec<Type>, Vec<Type>) = options.into_iter().partition(|x| matches!(x, Type::Param(_)));
+
+            let mut rest = Vec::new();
+            for found_option in options_of(&found, enclosing){
+                if !others.iter().any(|other| enclosing.is_assignable(other, &found_option)) {
+                    rest.push(found_option);
+                }
+            }
+
+            if let ([parameter], false) = (parameters.as_slice(), rest.is_empty()) {
+                infer(parameter, &union_of(rest), bindings, enclosing);
+            }
+        },
+        _ => ()
+    }
+}
+
+//fills in the inferred parameters, the ones nothing was inferred for could be anything
+fn substitute(r#type : &Type, bindings : &HashMap<String, Type>) -> Type{
+    r#type.replace(&|x| match x {
+        Type::Param(name) => Some(bindings.get(name).cloned().unwrap_or(Type::AnyType)),
+        _ => None
+    })
+}
+
+//the result of a binary operator applied to the two types
+fn check_operation(operator : &Token, left_type : Type, right_type : Type, enclosing : &mut TypeEnvironment) -> Result<Type, Error>{
+    if let Some(result) = enclosing.get_operation(operator.r#type.clone(), left_type.clone(), right_type.clone()){
+        return Ok(result)
+    }
+
+    if enclosing.is_unbound(&left_type) || enclosing.is_unbound(&right_type) {
+        return infer_operation(operator, left_type, right_type, enclosing)
+    }
+
+    //we cannot say anything about untyped values, comparisons are still booleans
+    if left_type == Type::AnyType || right_type == Type::AnyType {
+        return match operator.r#type {
+            TokenType::GE | TokenType::GEQ | TokenType::LE | TokenType::LEQ
+                | TokenType::AND | TokenType::OR | TokenType::XOR => Ok(Type::BoolType),
+            _ => Ok(Type::AnyType)
+        }
+    }
+
+    Err(Error::InvalidOperands{
+        operator : operator.clone(),
+        operands : vec![left_type.to_string(), right_type.to_string()]
+    })
+}
+
+//an operand that is not worked out yet has to be whatever the operator can be used with, when only one
+//operation fits that is what it is, otherwise we cannot tell
+fn infer_operation(operator : &Token, left_type : Type, right_type : Type, enclosing : &mut TypeEnvironment) -> Result<Type, Error>{
+    let candidates : Vec<((Type, Type), Type)> = enclosing.operations_of(&operator.r#type).into_iter()
+        .filter(|((left, right), _)| {
+            let fits = |expected : &Type, found : &Type| enclosing.is_unbound(found) || expected == found;
+            fits(left, &left_type) && fits(right, &right_type)
+        })
+        .collect();
+
+    match candidates.as_slice() {
+        [((left, right), result)] => {
+            enclosing.is_assignable(left, &left_type);
+            enclosing.is_assignable(right, &right_type);
+            Ok(result.clone())
+        },
+        [] => Err(Error::InvalidOperands{
+            operator : operator.clone(),
+            operands : [left_type, right_type].iter()
+                .filter(|x| **x != Type::NullType)
+                .map(|x| enclosing.fill_in(x).to_string())
+                .collect()
+        }),
+        _ => Ok(Type::AnyType)
+    }
+}
+
+//a += b is checked as a = a + b, where the + can also be an overload
+fn check_compound_assign(target : &Expression, operator : &Token, value : &Expression, enclosing : &mut TypeEnvironment) -> Result<Type, Error>{
+    //the operation works on what the target is right now, which might be narrower than its declaration
+    let (target_type, current_type) = assignment_target_types(target, enclosing)?;
+    let value_type = check_expression(value, enclosing)?;
+
+    let applied = Token{
+        r#type : operator.r#type.compound_operator().unwrap_or(operator.r#type.clone()),
+        span : operator.span
+    };
+    let result_type = check_operation(&applied, current_type, value_type, enclosing)?;
+
+    let checked = enclosing.expect(&target_type, &result_type, value.span);
+    noting_inferred(checked, target, enclosing)?;
+    widen_target(target, &result_type, enclosing);
+
+    Ok(result_type)
+}
+
+fn check_assign(target : &Expression, value : &Expression, enclosing : &mut TypeEnvironment) -> Result<Type, Error>{
+    let value_type = check_expression(value, enclosing)?;
+
+    let (target_type, _) = assignment_target_types(target, enclosing)?;
+
+    let checked = enclosing.expect(&target_type, &value_type, value.span);
+    noting_inferred(checked, target, enclosing)?;
+    widen_target(target, &value_type, enclosing);
+
+    Ok(value_type)
+}
+
+//a mismatch with a variable that has no annotation points to where its type was inferred, since nothing
+//in the code says what it is
+fn noting_inferred(checked : Result<(), Error>, expression : &Expression, enclosing : &mut TypeEnvironment) -> Result<(), Error>{
+    let name = match &expression.kind {
+        ExpressionKind::LiteralID(name) => name,
+        _ => return checked
+    };
+
+    match (checked, enclosing.get_declared_value_type(name.to_string())) {
+        (Err(Error::TypeMismatch(mut mismatch)), Some(Type::Inferred(id))) => {
+            mismatch.inferred_from = Some((enclosing.origin_of(id), enclosing.fill_in(&Type::Inferred(id))));
+            Err(Error::TypeMismatch(mismatch))
+        },
+        (checked, _) => checked
+    }
+}
+
+//a variable that was narrowed can hold the assigned value from now on
+fn widen_target(target : &Expression, assigned_type : &Type, enclosing : &mut TypeEnvironment){
+    if let ExpressionKind::LiteralID(name) = &target.kind {
+        enclosing.widen(name.to_string(), assigned_type.clone());
+    }
+}
+
+//what can be assigned to something, which has to be a variable (that is not constant), a field or an index,
+//and the type it has right now
+fn assignment_target_types(target : &Expression, enclosing : &mut TypeEnvironment) -> Result<(Type, Type), Error>{
+    let target_types = match &target.kind {
+        ExpressionKind::LiteralID(name) => {
+            if enclosing.is_constant(name.to_string()) {
+                return Err(Error::ConstantReassignment{
+                    name : name.to_string(),
+                    span : target.span
+                })
+            }
+
+            //narrowings do not change what can be assigned, the declaration does
+            let current_type = check_expression(target, enclosing)?;
+            (enclosing.get_declared_value_type(name.to_string()).unwrap_or(Type::AnyType), current_type)
+        },
+        ExpressionKind::FieldAccess { .. } | ExpressionKind::Index { .. } => {
+            let target_type = check_expression(target, enclosing)?;
+            (target_type.clone(), target_type)
+        },
+        _ => return Err(Error::InvalidAssignmentTarget(target.span))
+    };
+
+    Ok(target_types)
+}
+
+//what a type looks like inside, structs (generic ones with their arguments filled in) are what they were
+//declared as
+fn fields_of(r#type : &Type, enclosing : &mut TypeEnvironment) -> Type{
+    match enclosing.resolve(r#type) {
+        Type::CustomType(name) => enclosing.instantiate(name, &[]).unwrap_or(Type::AnyType),
+        Type::Applied { name, arguments } => enclosing.instantiate(name, &arguments).unwrap_or(Type::AnyType),
+        other => other
+    }
+}
+
+//the type of target.field, objects and structs know their fields, anything else does not have any
+fn field_type(target_type : &Type, field : &str, span : Span, enclosing : &mut TypeEnvironment) -> Result<Type, Error>{
+    match fields_of(target_type, enclosing) {
+        Type::AnyType => Ok(Type::AnyType),
+        Type::ObjectType { keys, types } | Type::Struct { keys, types } => {
+            match keys.iter().position(|x| x == field) {
+                Some(index) => Ok(types[index].clone()),
+                None => Err(Error::FieldNotFound{
+                    field : field.to_string(),
+                    span
+                })
+            }
+        },
+        _ => Err(Error::FieldNotFound{
+            field : field.to_string(),
+            span
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::*;
+
+    fn check(text : &'static str) -> Result<(), Error>{
+        type_check(parse(lex(text).unwrap()).unwrap())
+    }
+
+    //the expected and found type of a mismatch
+    fn mismatch(result : Result<(), Error>) -> Option<(Type, Type)>{
+        match result {
+            Err(Error::TypeMismatch(mismatch)) => Some((mismatch.expected, mismatch.found)),
+            _ => None
+        }
+    }
+
+    #[test]
+    fn annotations_are_checked(){
+        assert!(check("let x : num = 1; let s : string = \"a\";").is_ok());
+        assert_eq!(mismatch(check("let x : num = \"a\";")), Some((Type::NumType, Type::StrType)));
+        assert_eq!(mismatch(check("let x : num = 1; x = true;")), Some((Type::NumType, Type::BoolType)));
+    }
+
+    #[test]
+    fn functions_are_checked(){
+        assert!(check("fn f(x : num) -> num { return x + 1; } let y : num = f(1);").is_ok());
+        assert_eq!(mismatch(check("fn f(x : num) -> num { return x; } f(\"a\");")), Some((Type::NumType, Type::StrType)));
+        assert_eq!(mismatch(check("fn f(x : num) -> string { return x; }")), Some((Type::StrType, Type::NumType)));
+        assert!(matches!(check("fn f(x : num) -> num { return x; } f(1, 2);"), Err(Error::ArityMismatch{ expected : 1, found : 2, .. })));
+    }
+
+    #[test]
+    fn names_have_to_exist(){
+        assert!(matches!(check("y;"), Err(Error::UndefinedVariable{ .. })));
+        assert!(matches!(check("let x : nope = 1;"), Err(Error::TypeNotFound{ .. })));
+        assert!(matches!(check("const c = 1; c = 2;"), Err(Error::ConstantReassignment{ .. })));
+    }
+
+    #[test]
+    fn operators_need_fitting_operands(){
+        assert!(matches!(check("let b : bool = true; b + 1;"), Err(Error::InvalidOperands{ .. })));
+        assert!(matches!(check("if 1 { }"), Err(Error::InvalidCondition{ .. })));
+    }
+
+    #[test]
+    fn aliases_and_unions(){
+        assert!(check("type maybe = num | nil; let a : maybe = nil; a = 2;").is_ok());
+        assert!(matches!(check("type maybe = num | nil; let a : maybe = \"a\";"), Err(Error::TypeMismatch(_))));
+    }
+
+    #[test]
+    fn interpolated_values_have_to_be_stringifiable(){
+        assert!(check(r#"let n : num = 1; let s : string = "${n}";"#).is_ok());
+        assert!(matches!(check(r#"fn f() { } let s : string = "${f}";"#), Err(Error::NotStringifiable{ .. })));
+    }
+
+    #[test]
+    fn missing_types_are_reported_where_they_were_used(){
+        let used = Span::new(Position{ offset : 8, line : 0, column : 8 }, Position{ offset : 12, line : 0, column : 12 });
+
+        match TypeEnvironment::global().check_type("nope".to_string(), Type::NumType, used) {
+            Err(Error::TypeNotFound{ span, .. }) => assert_eq!(span, used),
+            other => panic!("expected a missing type, found {:?}", other)
+        }
+    }
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn run_prints_what_the_program_prints(){
+    prints("let a = 1;\nprint(a + 1, \"two\");", "2 two\n");
+}
+
+#[test]
+fn check_does_not_run_the_program(){
+    let output = bifrost("check", "print(1);");
+
+    assert!(output.success);
+    assert_eq!(output.stdout, "");
+}
+
+#[test]
+fn type_errors_stop_the_program_before_it_runs(){
+    let output = fails("print(1); let x : num = \"a\";", &["mismatched types"]);
+
+    assert_eq!(output.stdout, "");
+    assert_eq!(output.code, Some(1));
+}
+
+#[test]
+fn errors_are_rendered_against_the_file(){
+    let output = bifrost("check", "let a = 1;\nlet b : num = \"b\";");
+
+    assert!(output.stderr.contains(".bf:2:15"), "{}", output.stderr);
+    assert!(output.stderr.contains("2 | let b : num = \"b\";"), "{}", output.stderr);
+}
+
+#[test]
+fn tokens_are_listed_with_their_position(){
+    let output = bifrost("tokens", "let a = 1;\nprint(a);");
+
+    assert!(output.success);
+    assert!(output.stdout.starts_with("1:1\tLET\n1:5\tID(\"a\")\n"), "{}", output.stdout);
+    assert!(output.stdout.ends_with("2:9\tSEMICOLON\n2:10\tEOF\n"), "{}", output.stdout);
+}
+
+#[test]
+fn ast_prints_the_tree(){
+    let output = bifrost("ast", "let a = 1;");
+
+    assert!(output.success);
+    assert!(output.stdout.contains("Declaration"), "{}", output.stdout);
+}
+
+#[test]
+fn wrong_usage(){
+    assert_eq!(arguments(&[]).code, Some(2));
+    assert!(arguments(&["help"]).stdout.starts_with("usage: bifrost"));
+
+    let unknown = arguments(&["fly", "main.bf"]);
+    assert_eq!(unknown.code, Some(2));
+    assert!(unknown.stderr.contains("unknown command `fly`"));
+
+    let missing = arguments(&["run", "does-not-exist.bf"]);
+    assert_eq!(missing.code, Some(2));
+    assert!(missing.stderr.contains("could not read `does-not-exist.bf`"));
+}
@@ -0,0 +1,88 @@
+//runs the bifrost binary on small programs, every test file includes this with `mod common;`
+#![allow(dead_code)]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static FILES : AtomicUsize = AtomicUsize::new(0);
+
+pub struct Output{
+    pub stdout : String,
+    pub stderr : String,
+    pub code : Option<i32>,
+    pub success : bool
+}
+
+impl Output{
+    fn new(output : std::process::Output) -> Self{
+        let output = Output{
+            stdout : String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr : String::from_utf8_lossy(&output.stderr).to_string(),
+            code : output.status.code(),
+            success : output.status.success()
+        };
+
+        //whatever happened, bifrost itself should never crash
+        assert!(!output.stderr.contains("panicked"), "bifrost panicked:\n{}", output.stderr);
+        output
+    }
+}
+
+//runs bifrost with the arguments the way they would be typed on the command line
+pub fn arguments(arguments : &[&str]) -> Output{
+    let output = Command::new(env!("CARGO_BIN_EXE_bifrost"))
+        .args(arguments)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+
+    Output::new(output)
+}
+
+//runs a bifrost command (run, check, ast or tokens) on the source, which is written to a file of its own
+pub fn bifrost(command : &str, source : &str) -> Output{
+    let path = std::env::temp_dir().join(format!("bifrost-test-{}-{}.bf", std::process::id(), FILES.fetch_add(1, Ordering::SeqCst)));
+    std::fs::write(&path, source).unwrap();
+
+    let output = arguments(&[command, path.to_str().unwrap()]);
+    let _ = std::fs::remove_file(&path);
+
+    output
+}
+
+//feeds the input to the repl line by line, like someone typing it
+pub fn repl(input : &str) -> Output{
+    let mut child = Command::new(env!("CARGO_BIN_EXE_bifrost"))
+        .arg("repl")
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+
+    Output::new(child.wait_with_output().unwrap())
+}
+
+//the program runs without errors and prints exactly this
+pub fn prints(source : &str, expected : &str){
+    let output = bifrost("run", source);
+
+    assert!(output.success, "expected the program to run, it failed with:\n{}", output.stderr);
+    assert_eq!(output.stdout, expected);
+}
+
+//the program is rejected (or stops) with an error whose diagnostic contains every one of the parts
+pub fn fails(source : &str, parts : &[&str]) -> Output{
+    let output = bifrost("run", source);
+
+    assert!(!output.success, "expected an error, the program printed:\n{}", output.stdout);
+    for part in parts{
+        assert!(output.stderr.contains(part), "expected `{}` in the error:\n{}", part, output.stderr);
+    }
+
+    output
+}
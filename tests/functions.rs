@@ -0,0 +1,21 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn recursion_that_never_stops_is_an_error(){
+    fails("fn f(n) { return f(n + 1); } f(0);", &["calls are nested more than 1000 levels deep", "one call too many"]);
+}
+
+#[test]
+fn deep_recursion_below_the_limit_works(){
+    prints("fn count(n) { if n == 0 { return 0; } return 1 + count(n - 1); } print(count(999));", "999\n");
+}
+
+#[test]
+fn the_depth_is_given_back_after_an_error(){
+    let output = repl("fn f(n) { return f(n + 1); }\nf(0);\nfn g(n) { if n == 0 { return \"done\"; } return g(n - 1); }\ng(999);\n");
+
+    assert!(output.stderr.contains("calls are nested more than 1000 levels deep"), "{}", output.stderr);
+    assert!(output.stdout.contains("done"), "{}", output.stdout);
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn bindings_have_to_be_names(){
+    let output = fails("for 5 in [1] { print(1); } print(2);", &["expected a name, found `5`"]);
+
+    //the block of the broken loop is skipped as a whole instead of causing more errors
+    assert_eq!(output.stderr.matches("error").count(), 1);
+}
+
+#[test]
+fn fractional_steps_do_not_accumulate_rounding_errors(){
+    let output = bifrost("run", "for i = 0 to 1 step 0.1 { print(i); }");
+
+    assert!(output.success);
+    assert_eq!(output.stdout.lines().count(), 11);
+    assert_eq!(output.stdout.lines().last(), Some("1"));
+}
+
+#[test]
+fn huge_ranges_are_not_built_up_front(){
+    prints("for i = 0 to 1e12 { print(i); break; }", "0\n");
+}
+
+#[test]
+fn zero_step_is_its_own_error(){
+    fails("let s : any = 0; for i = 0 to 3 step s { }", &["a `step` of 0 would never finish", "this is 0"]);
+}
+
+#[test]
+fn bounds_have_to_be_numbers(){
+    fails("for i = 0 to \"a\" { }", &["mismatched types", "expected `num`, found `string`"]);
+    fails("let s : any = \"a\"; for i = s to 3 { }", &["the bounds of a for loop have to be `num`, found `string`"]);
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn decimal_literals(){
+    prints("print(0.5, 12.25, 100, 1.0, 0.1 + 0.2);", "0.5 12.25 100 1 0.30000000000000004\n");
+}
+
+#[test]
+fn literals_keep_the_text_they_were_written_with(){
+    let output = bifrost("ast", "1.50;");
+
+    assert!(output.stdout.contains("\"1.50\""), "{}", output.stdout);
+}
+
+#[test]
+fn number_literals_out_of_range(){
+    let huge = format!("print(1{});", "0".repeat(400));
+
+    fails(&huge, &["number literal is out of range", "too large for a `num`"]);
+}
+
+#[test]
+fn prefixed_and_exponent_literals(){
+    prints("print(0x1F, 0b101, 0o17, 1_000, 2.5e3, 2.5E+3, .5, 1e-3);", "31 5 15 1000 2500 2500 0.5 0.001\n");
+}
+
+#[test]
+fn exponents_out_of_range(){
+    fails("print(1e400);", &["number literal is out of range"]);
+}
+
+#[test]
+fn malformed_number_literals(){
+    fails("print(0x);", &["malformed number", "expected digits after `0x`"]);
+    fails("print(1e);", &["malformed number", "expected digits after the exponent"]);
+    fails("print(0b102);", &["malformed number", "`2` is not a binary digit"]);
+}
+
+#[test]
+fn literal_patterns_match_by_value(){
+    prints("let n : num = 31; print(match n { 0x1F => \"hex\", _ => \"other\" });", "hex\n");
+}
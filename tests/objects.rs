@@ -0,0 +1,62 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn object_literals(){
+    prints("let x = 1; let o = { x, y : 2 }; print(o);", "{ x : 1, y : 2 }\n");
+}
+
+#[test]
+fn spreads(){
+    prints("let o = { x : 1, y : 2 }; print({ ...o, z : 3 });", "{ x : 1, y : 2, z : 3 }\n");
+    prints("print({ ...{ a : 1 }, a : 2 });", "{ a : 2 }\n");
+    fails("let o = { ...5 };", &["`...` cannot be applied to `num`", "cannot be spread"]);
+}
+
+#[test]
+fn braces_with_statements_are_blocks(){
+    prints("let b = { 1; }; print(b);", "1\n");
+}
+
+#[test]
+fn empty_braces_are_empty_objects_where_a_value_is_expected(){
+    prints("let o : {} = {}; print(o, [{}], type({})); fn f() {} print(f()); if true {} else {}", "{} [{}] object\nnil\n");
+    fails("let n : num = {};", &["expected `num`, found `{}`"]);
+}
+
+#[test]
+fn object_literals_are_typed(){
+    fails("let o : { a : num } = { a : \"x\" };", &["expected `{ a : num }`, found `{ a : string }`"]);
+}
+
+#[test]
+fn fields_and_indices(){
+    prints("let o = { a : 1, b : { c : [1, 2, 3] } }; print(o.b.c[2], o.a, \"abc\"[1]);", "3 1 b\n");
+}
+
+#[test]
+fn fields_and_indices_can_be_assigned_to(){
+    prints("let o = { a : 1, b : { c : [1, 2, 3] } }; o.b.c[0] = 9; o.a += 1; print(o);", "{ a : 2, b : { c : [9, 2, 3] } }\n");
+    prints("let a = [1, 2]; a[1] += 5; print(a);", "[1, 7]\n");
+}
+
+#[test]
+fn calling_a_field(){
+    prints("let o = { f : fn(x : num) -> num { return x + 1; } }; print(o.f(1));", "2\n");
+}
+
+#[test]
+fn member_access_nodes(){
+    let output = bifrost("ast", "o.a[1].f(2);");
+
+    for node in ["MethodCall", "Index", "FieldAccess"]{
+        assert!(output.stdout.contains(node), "no {} in:\n{}", node, output.stdout);
+    }
+}
+
+#[test]
+fn missing_fields_and_indices(){
+    fails("let o = { a : 1 }; print(o.z);", &["no field `z`", "unknown field"]);
+    fails("let a = [1, 2]; print(a[5]);", &["index 5 is out of bounds for a length of 2"]);
+}
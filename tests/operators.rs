@@ -0,0 +1,56 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn unary_minus(){
+    prints("let a = 3; print(-a, - -a, -2 ** 2);", "-3 3 -4\n");
+}
+
+#[test]
+fn arithmetic_operators(){
+    prints("print(7 % 3, -7 % 3, 2 ** 10, 2 ** 3 ** 2);", "1 -1 1024 512\n");
+}
+
+#[test]
+fn bitwise_operators(){
+    prints("print(6 & 3, 6 | 3, 6 ^ 3, 1 << 4, 256 >> 4);", "2 7 5 16 16\n");
+}
+
+#[test]
+fn compound_assignment(){
+    prints("let a = 5; a += 2; a -= 1; a *= 3; a /= 2; print(a); let s = \"a\"; s += \"b\"; print(s);", "9\nab\n");
+    fails("let s : string = \"a\"; s -= 1;", &["`-` cannot be applied to `string` and `num`"]);
+}
+
+#[test]
+fn compound_assignment_evaluates_its_target_once(){
+    prints(
+        "let calls = 0; fn f() -> num { calls += 1; return 0; } let a = [1]; a[f()] += 1; let o = { x : [2] }; o.x[f()] *= 3; print(a, o.x, calls);",
+        "[2] [6] 2\n"
+    );
+}
+
+#[test]
+fn operands_are_checked(){
+    fails("print(\"a\" - 1);", &["`-` cannot be applied to `string` and `num`", "overload - (a : string, b : num)"]);
+    fails("print(1 / 0);", &["division by zero", "the right side is 0"]);
+}
+
+#[test]
+fn precedence(){
+    prints("let a = 1; let b = 1; print(a == b and 2 == 2, 1 + 2 * 3, (1 + 2) * 3, 2 * 3 ** 2, 1 < 2 == true);", "true 7 9 18 true\n");
+    prints("print(true or false and false, 1 | 2 ^ 3 & 4);", "true 3\n");
+}
+
+#[test]
+fn associativity(){
+    prints("print(10 - 4 - 3, 2 ** 3 ** 2);", "3 512\n");
+    prints("let a = 0; let b = 0; a = b = 3; print(a, b);", "3 3\n");
+}
+
+#[test]
+fn assignment_targets_have_to_be_places(){
+    fails("1 = 2;", &["invalid left-hand side of assignment", "cannot be assigned to"]);
+    fails("let a = 1; a + 1 = 2;", &["invalid left-hand side of assignment"]);
+}
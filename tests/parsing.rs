@@ -0,0 +1,57 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn bindings_have_to_be_names(){
+    fails("let 3 = 4;", &["expected a name, found `3`"]);
+    fails("fn f(1) 2;", &["expected a name, found `1`"]);
+}
+
+#[test]
+fn empty_and_comment_only_files(){
+    for source in ["", "// hi\n", "/* a\nb */", "/// doc\n"]{
+        for command in ["run", "check", "ast", "tokens"]{
+            let output = bifrost(command, source);
+            assert!(output.success, "`{}` failed on {:?}:\n{}", command, source, output.stderr);
+        }
+    }
+}
+
+#[test]
+fn every_parse_error_is_reported(){
+    let output = fails("let = 1; let y : num = ; print(2);", &["expected a name, found `=`", "expected an expression, found `;`"]);
+
+    assert_eq!(output.stderr.matches("error:").count(), 2);
+}
+
+#[test]
+fn missing_semicolons(){
+    fails("print(1)", &["expected `;`, found the end of the file", "statements have to end with `;`"]);
+}
+
+#[test]
+fn errors_at_the_end_of_the_file_point_at_it(){
+    for source in ["let", "for", "let x =", "fn f(", "overload", "struct S {", "match x {"]{
+        let output = bifrost("check", source);
+
+        assert!(!output.success);
+        assert!(output.stderr.contains("found the end of the file"), "{:?} gave:\n{}", source, output.stderr);
+        assert!(!output.stderr.contains("<unknown>"), "{:?} gave:\n{}", source, output.stderr);
+    }
+}
+
+#[test]
+fn overloads_need_an_operator_and_a_result(){
+    fails("overload ( _ ) break; print(1);", &["found `(`"]);
+    fails("overload + (a : num) a;", &["expected `->`, found `a`"]);
+}
+
+#[test]
+fn overloads(){
+    prints("struct v { x : num }
+overload + (a : v, b : v) -> v v { x : a.x + b.x };
+overload - (a : v) -> v v { x : 0 - a.x };
+let s : v = v { x : 1 } + v { x : 2 };
+print(s.x, (-s).x);", "3 -3\n");
+}
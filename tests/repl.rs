@@ -0,0 +1,85 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn values_are_printed_with_their_type(){
+    let output = repl("fn double(x : num) -> num { return x * 2; }\ndouble(2);\n");
+
+    assert!(output.success);
+    assert!(output.stdout.contains("<fn double> : fn(num) -> num"), "{}", output.stdout);
+    assert!(output.stdout.contains("4 : num"), "{}", output.stdout);
+}
+
+#[test]
+fn declarations_persist_between_inputs(){
+    let output = repl("let a : num = 1;\na = a + 1;\nprint(a);\n");
+
+    assert!(output.stdout.contains("2\n"), "{}", output.stdout);
+    assert_eq!(output.stderr, "");
+}
+
+#[test]
+fn errors_do_not_end_the_session(){
+    let output = repl("b;\nprint(\"still here\");\n");
+
+    assert!(output.success);
+    assert!(output.stderr.contains("cannot find `b` in this scope"), "{}", output.stderr);
+    assert!(output.stdout.contains("still here"), "{}", output.stdout);
+}
+
+#[test]
+fn commands(){
+    let output = repl(":type 1 + 2\n:tokens a\n:help\n");
+
+    assert!(output.stdout.contains("num"), "{}", output.stdout);
+    assert!(output.stdout.contains("ID(\"a\")"), "{}", output.stdout);
+    assert!(output.stdout.contains(":reset"), "{}", output.stdout);
+}
+
+#[test]
+fn reset_forgets_declarations(){
+    let output = repl("let a = 1;\n:reset\na;\n");
+
+    assert!(output.stderr.contains("cannot find `a` in this scope"), "{}", output.stderr);
+}
+
+#[test]
+fn inputs_ending_in_a_brace_do_not_need_a_semicolon(){
+    let output = repl("let o = { a : 1 }\nprint(o.a)\nstruct P { x : num }\nlet p = P { x : 2 }\nprint(p.x)\n");
+
+    assert!(!output.stderr.contains("error"), "{}", output.stderr);
+    assert!(output.stdout.contains("1\n"));
+    assert!(output.stdout.contains("2\n"));
+}
+
+#[test]
+fn comments_are_not_terminated(){
+    let output = repl("// hi\nprint(3) // trailing\n");
+
+    assert!(!output.stderr.contains("error"), "{}", output.stderr);
+    assert!(output.stdout.contains("3\n"));
+}
+
+#[test]
+fn failed_inputs_declare_nothing(){
+    let output = repl("let d = 1 / 0;\nprint(d);\n");
+
+    assert!(output.stderr.contains("cannot find `d`"), "{}", output.stderr);
+}
+
+#[test]
+fn inputs_failing_late_keep_none_of_their_declarations(){
+    let output = repl("fn f() -> num { return 1; } let b = 2; 1 / 0;\nb;\nf();\nlet b = 3; fn f() -> num { return b; }\nf();\n");
+
+    assert!(output.stderr.contains("cannot find `b`"), "{}", output.stderr);
+    assert!(output.stderr.contains("cannot find `f`"), "{}", output.stderr);
+    assert!(output.stdout.contains("3 : num"), "{}", output.stdout);
+}
+
+#[test]
+fn functions_declared_in_an_input_see_later_changes(){
+    let output = repl("let c = 0; fn bump() -> num { c = c + 1; return c; }\nbump();\nbump();\nc;\n");
+
+    assert!(output.stdout.contains("2 : num\n> 2 : num"), "{}", output.stdout);
+}
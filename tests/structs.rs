@@ -0,0 +1,126 @@
+mod common;
+
+use common::*;
+
+const VEC3 : &str = "struct vec3 { x : num, y : num, z : num }
+
+impl vec3 {
+    fn zero() -> vec3 {
+        return vec3 { x : 0, y : 0, z : 0 };
+    }
+
+    fn dot(self, other : vec3) -> num {
+        return self.x * other.x + self.y * other.y + self.z * other.z;
+    }
+
+    fn length(self) -> num self.dot(self) ** 0.5;
+}
+";
+
+const SHAPE : &str = "enum Shape {
+    Circle(num),
+    Rect { w : num, h : num },
+    Point
+}
+";
+
+#[test]
+fn struct_literals_and_fields(){
+    prints("struct P { x : num, y : num } let p = P { x : 1, y : 2 }; p.x = 5; print(p.x + p.y, p);", "7 P { x : 5, y : 2 }\n");
+}
+
+#[test]
+fn struct_literals_are_checked(){
+    fails("struct P { x : num, y : num } let p = P { x : 1 };", &["missing field `y` in `P`"]);
+    fails("struct P { x : num } let p = P { x : 1, x : 2 };", &["field `x` is set more than once", "first set here"]);
+    fails("struct P { x : num } let p = P { x : 1, w : 2 };", &["no field `w`"]);
+    fails("struct P { x : num } let p = P { x : \"a\" };", &["expected `num`, found `string`"]);
+    fails("type T = { x : num }; let t = T { x : 1 };", &["`T` is not a struct"]);
+}
+
+#[test]
+fn fields_are_typed_from_the_declaration(){
+    fails("struct P { x : num } let p : P = P { x : 1 }; let s : string = p.x;", &["expected `string`, found `num`"]);
+    fails("struct P { x : num } let p = P { x : 1 }; print(p.z);", &["no field `z`"]);
+}
+
+#[test]
+fn structs_without_fields(){
+    prints("struct E {} enum S { A {}, B } let e = E {}; print(e, e is E, S::A {}); let running = false; while running {}", "E {} true S::A {}\n");
+    fails("struct E {} let e = E { x : 1 };", &["no field `x`"]);
+}
+
+#[test]
+fn match_arms_with_longer_types_are_not_struct_literals(){
+    prints("let v : num | string = 1; print(match v { n : num | string => n, });", "1\n");
+}
+
+#[test]
+fn structs_are_nominal(){
+    fails("struct A { x : num } struct B { x : num } let a : A = B { x : 1 };", &["expected `A`, found `B`"]);
+}
+
+#[test]
+fn methods_and_associated_functions(){
+    prints(&format!("{}let v = vec3 {{ x : 3, y : 4, z : 0 }};
+print(v.length(), vec3::length(v), vec3::zero().dot(v));", VEC3), "5 5 0\n");
+}
+
+#[test]
+fn methods_are_checked(){
+    fails(&format!("{}let v = vec3::zero(); v.nope();", VEC3), &["no function `nope` for `vec3`"]);
+    fails(&format!("{}vec3::nope();", VEC3), &["no function `nope` for `vec3`"]);
+    fails(&format!("{}let v = vec3::zero(); v.zero();", VEC3), &["`vec3::zero` is not a method", "call it as `vec3::zero(...)`"]);
+    fails(&format!("{}let v : vec3 = vec3::zero(); v.dot(1);", VEC3), &["expected `vec3`, found `num`"]);
+}
+
+#[test]
+fn impl_blocks_need_a_declared_type(){
+    fails("impl nope { fn f() -> num { return 1; } }", &["cannot find type `nope`"]);
+}
+
+#[test]
+fn enum_variants(){
+    prints(&format!("{}let c : Shape = Shape::Circle(2);
+print(c, Shape::Rect {{ w : 1, h : 2 }}, Shape::Point, c == Shape::Circle(2), c == Shape::Circle(3));", SHAPE),
+        "Shape::Circle(2) Shape::Rect { w : 1, h : 2 } Shape::Point true false\n");
+}
+
+#[test]
+fn enum_variants_are_checked(){
+    fails(&format!("{}let s : Shape = Shape::Square;", SHAPE), &["no variant `Square` in `Shape`", "the variants are `Shape::Circle`, `Shape::Rect`, `Shape::Point`"]);
+    fails(&format!("{}let s : Shape = Shape::Circle(\"a\");", SHAPE), &["expected `num`, found `string`"]);
+    fails(&format!("{}let s : Shape = Shape::Rect {{ w : 1 }};", SHAPE), &["missing field `h` in `Shape::Rect`"]);
+    fails(&format!("{}let s : num = Shape::Point;", SHAPE), &["expected `num`, found `Shape`"]);
+}
+
+#[test]
+fn match_arms_patterns_and_guards(){
+    prints(&format!("{}fn area(s : Shape) -> num {{
+    return match s {{
+        Shape::Circle(r) => 3 * r * r,
+        Shape::Rect {{ w, h }} if w == h => w * w,
+        Shape::Rect {{ w, h : height }} => w * height,
+        Shape::Point => 0
+    }};
+}}
+print(area(Shape::Circle(2)), area(Shape::Rect {{ w : 2, h : 2 }}), area(Shape::Rect {{ w : 2, h : 3 }}), area(Shape::Point));", SHAPE),
+        "12 4 6 0\n");
+
+    prints("let xs : num[] = [1, 2, 3];
+print(match xs { [] => \"empty\", [first, ...rest] => \"${first} then ${rest}\" });", "1 then [2, 3]\n");
+
+    prints("let v : num | string = \"s\"; print(match v { n : num => n + 1, s : string => s + \"!\" });", "s!\n");
+}
+
+#[test]
+fn matches_have_to_be_exhaustive(){
+    fails(&format!("{}let s : Shape = Shape::Point; print(match s {{ Shape::Point => 1 }});", SHAPE),
+        &["patterns `Shape::Circle(_)` and `Shape::Rect { .. }` are not covered", "2 cases not covered"]);
+    fails("let b : bool = true; print(match b { true => 1 });", &["pattern `false` is not covered"]);
+}
+
+#[test]
+fn unreachable_arms(){
+    fails("let b : bool = true; print(match b { _ => 1, true => 2 });", &["unreachable pattern", "never reached"]);
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn narrowing(){
+    prints("fn describe(x : num | string | nil) -> string {
+    if x == nil {
+        return \"nothing\";
+    } else if x is num {
+        let n : num = x + 1;
+        return \"number\";
+    } else {
+        return \"string \" + x;
+    }
+}
+print(describe(1), describe(\"a\"), describe(nil));", "number string a nothing\n");
+
+    prints("fn total(x : num | nil, y : num | nil) -> num {
+    if x != nil and y != nil {
+        return x + y;
+    }
+    if type(x) == \"num\" {
+        return x;
+    } else {
+        return 0;
+    }
+}
+print(total(1, 2), total(3, nil), total(nil, nil));", "3 3 0\n");
+}
+
+#[test]
+fn narrowing_in_while_loops(){
+    prints("let x : num | nil = 3; while x != nil { let n : num = x; print(n); x = nil; }", "3\n");
+}
+
+#[test]
+fn narrowing_only_applies_inside_the_branch(){
+    fails("let x : num | nil = 1; let y : num = x;", &["mismatched types"]);
+    fails("fn f(x : num | string) -> num { if x is num { return x; } else { return x; } }", &["expected `num`, found `string`"]);
+}
+
+#[test]
+fn generic_functions_infer_their_arguments(){
+    prints("fn first<T>(xs : T[]) -> T { return xs[0]; }
+let n : num = first([1, 2]);
+let s : string = first([\"a\"]);
+print(n, s);", "1 a\n");
+    fails("fn first<T>(xs : T[]) -> T { return xs[0]; } let s : string = first([1]);", &["expected `string`, found `num`"]);
+}
+
+#[test]
+fn generic_structs(){
+    prints("struct Pair<A, B> { first : A, second : B }
+fn swap<A, B>(p : Pair<A, B>) -> Pair<B, A> { return Pair { first : p.second, second : p.first }; }
+let q : Pair<string, num> = swap(Pair { first : 1, second : \"one\" });
+print(q.first, q.second);", "one 1\n");
+    fails("struct Pair<A, B> { first : A, second : B } let p : Pair<num> = Pair { first : 1, second : 2 };", &["`Pair` takes 2 type arguments but 1 type argument was supplied"]);
+    fails("struct Pair<A, B> { first : A, second : B } let p : Pair<num, num> = Pair { first : 1, second : 2 }; let s : string = p.first;", &["expected `string`, found `num`"]);
+}
+
+#[test]
+fn generic_aliases(){
+    prints("type maybe<T> = T | nil; let a : maybe<num> = nil; let b : maybe<num> = 2; print(a, b);", "nil 2\n");
+    fails("type maybe<T> = T | nil; let a : maybe<num> = \"a\";", &["mismatched types"]);
+}
+
+#[test]
+fn is_checks_against_what_aliases_stand_for(){
+    prints("type N = num; let x : num | string = 3; if x is N { print(\"num\"); }
+type maybe<T> = T | nil; let v : num | string | nil = \"s\";
+print(v is maybe<num>, v is maybe<string>, match v { n : maybe<num> => \"maybe\", _ => \"string\" });", "num\nfalse true string\n");
+}
+
+#[test]
+fn inferred_variables_keep_their_type(){
+    fails("let x = 5; x = \"s\";", &["expected `num`, found `string`", ":inferred `num`", "this name has no annotation"]);
+    fails("struct P { x : num } let p = P { x : 1 }; let s : string = p.x;", &["expected `string`, found `num`"]);
+}
+
+#[test]
+fn inferred_parameters_and_results(){
+    prints("let inc = fn(n) n + 1;
+fn fact(n) {
+    if n < 1 { return 1; }
+    return n * fact(n - 1);
+}
+print(inc(2), fact(5));", "3 120\n");
+    fails("let inc = fn(n) n + 1; inc(\"a\");", &["expected `num`, found `string`"]);
+    fails("fn half(n) n / 2; let h : string = half;", &["found `fn(num) -> num`"]);
+    fails("fn f(n) { let m = n * 2; return m; } let q = f(2); q = true;", &["expected `num`, found `bool`", ":inferred `num`"]);
+}
+
+#[test]
+fn what_cannot_be_inferred_is_any(){
+    fails("fn both(a, b) a + b; let h : string = both;", &["found `fn(any, any) -> any`"]);
+    prints("fn show(v) { print(v); } show(1); show(\"two\");", "1\ntwo\n");
+}
+
+#[test]
+fn aliases_defined_as_themselves(){
+    prints("type list = list[] | nil; let l : list = [[nil], nil]; print(l);", "[[nil], nil]\n");
+    fails("type L = L | nil; let a : L = 5;", &["type `L` is defined as itself"]);
+    fails("type A = num; type A = A | nil;", &["type `A` is defined as itself"]);
+}
+
+#[test]
+fn impl_methods_are_inferred(){
+    fails("struct P { x : num }
+impl P { fn get(self) { return self.x; } }
+let p = P { x : 1 };
+let s : string = p.get();", &["expected `string`, found `num`"]);
+    fails("struct P { x : num }
+impl P { fn scaled(self, by) self.x * by; }
+let p = P { x : 1 };
+p.scaled(\"a\");", &["expected `num`, found `string`"]);
+}